@@ -0,0 +1,261 @@
+use std::fs;
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::database::report::render_daily;
+use crate::database::sqlite::{init_db, load_latest_daily};
+use crate::database::scheduler::{register_job, ScheduledJob};
+use crate::database::calendar::WorkCalendar;
+use crate::notifier::{send_report_to_channels, OutgoingReport};
+
+const CONFIG_FILE: &str = "auto_submit.json";
+const JOB_NAME: &str = "daily_submit";
+
+/// 日报自动提交设置
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutoSubmitConfig {
+    pub enabled: bool,
+    pub time: String, // 每天的提交时间，格式 HH:MM
+    pub channel_ids: Vec<String>,
+    pub user_id: Option<String>,
+    #[serde(default = "default_true")]
+    pub workdays_only: bool,
+    #[serde(default = "default_reminder")]
+    pub reminder_message: String,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_interval")]
+    pub retry_interval_minutes: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_reminder() -> String {
+    "今天的日报还没有填写，请尽快补充。".to_string()
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_interval() -> u32 {
+    10
+}
+
+impl Default for AutoSubmitConfig {
+    fn default() -> Self {
+        AutoSubmitConfig {
+            enabled: false,
+            time: "18:00".to_string(),
+            channel_ids: Vec::new(),
+            user_id: None,
+            workdays_only: true,
+            reminder_message: default_reminder(),
+            max_retries: default_max_retries(),
+            retry_interval_minutes: default_retry_interval(),
+        }
+    }
+}
+
+/// 发送记录
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SendHistoryEntry {
+    pub id: i64,
+    pub job: String,
+    pub report_date: String,
+    pub channel_id: String,
+    pub kind: String,   // report / reminder
+    pub status: String, // success / failed
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub sent_at: String,
+}
+
+pub fn load_config() -> AutoSubmitConfig {
+    fs::read_to_string(CONFIG_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(config: &AutoSubmitConfig) -> Result<(), String> {
+    NaiveTime::parse_from_str(&config.time, "%H:%M")
+        .map_err(|_| format!("提交时间格式不正确: {}, 应为HH:MM格式", config.time))?;
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("序列化自动提交设置失败: {}", e))?;
+    fs::write(CONFIG_FILE, json).map_err(|e| format!("保存自动提交设置失败: {}", e))
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS send_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            channel_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            sent_at TEXT NOT NULL,
+            UNIQUE(job, report_date, channel_id, kind)
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+// 某个渠道当天某类消息的发送状态：(status, attempts, 上次尝试时间)
+fn history_state(
+    conn: &Connection,
+    date: &str,
+    channel_id: &str,
+    kind: &str,
+) -> Result<Option<(String, u32, String)>, String> {
+    conn.query_row(
+        "SELECT status, attempts, sent_at FROM send_history WHERE job = ?1 AND report_date = ?2 AND channel_id = ?3 AND kind = ?4",
+        params![JOB_NAME, date, channel_id, kind],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn record_attempt(
+    conn: &Connection,
+    date: &str,
+    channel_id: &str,
+    kind: &str,
+    error: Option<&str>,
+) -> Result<(), String> {
+    let status = if error.is_none() { "success" } else { "failed" };
+    conn.execute(
+        "INSERT INTO send_history (job, report_date, channel_id, kind, status, attempts, error, sent_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7)
+         ON CONFLICT(job, report_date, channel_id, kind) DO UPDATE SET
+            status = excluded.status, attempts = attempts + 1, error = excluded.error, sent_at = excluded.sent_at",
+        params![JOB_NAME, date, channel_id, kind, status, error, Local::now().naive_local().format("%Y-%m-%d %H:%M:%S").to_string()],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 判断该渠道是否还需要发送：从未发送，或失败且未超过重试次数并已过重试间隔
+fn needs_send(state: &Option<(String, u32, String)>, config: &AutoSubmitConfig, now: NaiveDateTime) -> bool {
+    match state {
+        None => true,
+        Some((status, _, _)) if status == "success" => false,
+        Some((_, attempts, last)) => {
+            if *attempts > config.max_retries {
+                return false;
+            }
+            NaiveDateTime::parse_from_str(last, "%Y-%m-%d %H:%M:%S")
+                .map(|last| now - last >= chrono::Duration::minutes(config.retry_interval_minutes as i64))
+                .unwrap_or(true)
+        }
+    }
+}
+
+fn is_submit_day(config: &AutoSubmitConfig, now: NaiveDateTime) -> bool {
+    !config.workdays_only || WorkCalendar::for_user(config.user_id.as_deref()).is_workday(now.date())
+}
+
+/// 执行一次自动提交：有日报则按模板渲染后发送，没有日报则发送提醒
+///
+/// 返回本次实际尝试发送的渠道数
+pub fn run_auto_submit(config: &AutoSubmitConfig, now: NaiveDateTime) -> Result<usize, String> {
+    let conn = open_db()?;
+    let date = now.format("%Y-%m-%d").to_string();
+
+    let report = load_latest_daily(&conn, &date, config.user_id.as_deref())?;
+
+    let (kind, outgoing) = match &report {
+        Some(report) => ("report", OutgoingReport {
+            kind: "daily".to_string(),
+            title: format!("{} 日报", date),
+            markdown: render_daily(report)?,
+            attachments: Vec::new(),
+        }),
+        None => ("reminder", OutgoingReport {
+            kind: "reminder".to_string(),
+            title: format!("{} 日报提醒", date),
+            markdown: config.reminder_message.clone(),
+            attachments: Vec::new(),
+        }),
+    };
+
+    let mut pending = Vec::new();
+    for channel_id in &config.channel_ids {
+        let state = history_state(&conn, &date, channel_id, kind)?;
+        if needs_send(&state, config, now) {
+            pending.push(channel_id.clone());
+        }
+    }
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    for result in send_report_to_channels(&pending, &outgoing)? {
+        record_attempt(&conn, &date, &result.channel_id, kind, result.error.as_deref())?;
+    }
+    Ok(pending.len())
+}
+
+/// 在调度器上注册自动提交任务：每分钟检查一次，到达设定时间后提交当天日报，失败的渠道按间隔重试
+pub fn register_auto_submit_job() {
+    register_job(ScheduledJob::new("auto_submit", "自动提交日报", "* * * * *", "auto_submit"), |_, _| {
+        let config = load_config();
+        let now = Local::now().naive_local();
+        if !config.enabled || !is_submit_day(&config, now) {
+            return Ok(());
+        }
+        let time = NaiveTime::parse_from_str(&config.time, "%H:%M")
+            .map_err(|_| format!("提交时间格式不正确: {}", config.time))?;
+        if now.time() >= time {
+            run_auto_submit(&config, now)?;
+        }
+        Ok(())
+    });
+}
+
+#[tauri::command]
+pub fn get_auto_submit_config() -> AutoSubmitConfig {
+    load_config()
+}
+
+#[tauri::command]
+pub fn save_auto_submit_config(config: AutoSubmitConfig) -> Result<(), String> {
+    save_config(&config)
+}
+
+#[tauri::command]
+pub async fn run_auto_submit_now() -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(|| run_auto_submit(&load_config(), Local::now().naive_local()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_send_history(limit: Option<u32>) -> Result<Vec<SendHistoryEntry>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, job, report_date, channel_id, kind, status, attempts, error, sent_at
+         FROM send_history ORDER BY sent_at DESC, id DESC LIMIT ?1"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([limit.unwrap_or(50)], |row| {
+        Ok(SendHistoryEntry {
+            id: row.get(0)?,
+            job: row.get(1)?,
+            report_date: row.get(2)?,
+            channel_id: row.get(3)?,
+            kind: row.get(4)?,
+            status: row.get(5)?,
+            attempts: row.get(6)?,
+            error: row.get(7)?,
+            sent_at: row.get(8)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
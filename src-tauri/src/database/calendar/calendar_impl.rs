@@ -0,0 +1,386 @@
+use std::fs;
+use std::collections::{HashMap, HashSet};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::database::sqlite::init_db;
+
+// 随应用打包的法定节假日与调休数据
+const BUNDLED_HOLIDAYS: &str = include_str!("holidays_cn.json");
+// 用户更新或从ICS导入的数据，按日期覆盖打包数据
+const OVERRIDE_FILE: &str = "holidays.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 放假区间（含首尾两天）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HolidayRange {
+    pub name: String,
+    pub start: String,
+    pub end: String,
+}
+
+/// 调休上班日
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MakeupWorkday {
+    pub name: String,
+    pub date: String,
+}
+
+/// 节假日数据文件格式
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct HolidayData {
+    #[serde(default)]
+    pub holidays: Vec<HolidayRange>,
+    #[serde(default)]
+    pub workdays: Vec<MakeupWorkday>,
+}
+
+/// 个人请假记录
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PersonalLeave {
+    pub user_id: String,
+    pub date: String,
+    pub reason: Option<String>,
+}
+
+/// 某一天的日历信息
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DayInfo {
+    pub date: String,
+    pub is_workday: bool,
+    pub kind: String, // workday / weekend / holiday / makeup_workday / leave
+    pub name: Option<String>,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", date))
+}
+
+fn load_override_data() -> HolidayData {
+    match fs::read_to_string(OVERRIDE_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            eprintln!("解析节假日数据失败，仅使用内置数据: {}", e);
+            HolidayData::default()
+        }),
+        Err(_) => HolidayData::default(),
+    }
+}
+
+fn write_override_data(data: &HolidayData) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("序列化节假日数据失败: {}", e))?;
+    fs::write(OVERRIDE_FILE, json).map_err(|e| format!("保存节假日数据失败: {}", e))
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS personal_leave (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            date TEXT NOT NULL,
+            reason TEXT,
+            UNIQUE(user_id, date)
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+/// 工作日历：法定节假日、调休上班日和个人请假
+pub struct WorkCalendar {
+    holidays: HashMap<NaiveDate, String>,
+    workdays: HashMap<NaiveDate, String>,
+    leave: HashSet<NaiveDate>,
+}
+
+impl WorkCalendar {
+    fn apply(&mut self, data: &HolidayData) {
+        for range in &data.holidays {
+            let (start, end) = match (parse_date(&range.start), parse_date(&range.end)) {
+                (Ok(start), Ok(end)) => (start, end),
+                _ => {
+                    eprintln!("忽略无效的假期: {} {}~{}", range.name, range.start, range.end);
+                    continue;
+                }
+            };
+            let mut date = start;
+            while date <= end {
+                self.workdays.remove(&date);
+                self.holidays.insert(date, range.name.clone());
+                date += Duration::days(1);
+            }
+        }
+        for day in &data.workdays {
+            if let Ok(date) = parse_date(&day.date) {
+                self.holidays.remove(&date);
+                self.workdays.insert(date, day.name.clone());
+            }
+        }
+    }
+
+    /// 加载内置数据和用户更新的数据，不包含个人请假
+    pub fn load() -> Self {
+        let mut calendar = WorkCalendar {
+            holidays: HashMap::new(),
+            workdays: HashMap::new(),
+            leave: HashSet::new(),
+        };
+        match serde_json::from_str::<HolidayData>(BUNDLED_HOLIDAYS) {
+            Ok(data) => calendar.apply(&data),
+            Err(e) => eprintln!("解析内置节假日数据失败: {}", e),
+        }
+        calendar.apply(&load_override_data());
+        calendar
+    }
+
+    /// 加载日历并叠加某个用户的请假记录，user_id 为空时等同于 `load`
+    pub fn for_user(user_id: Option<&str>) -> Self {
+        let mut calendar = Self::load();
+        if let Some(user_id) = user_id.filter(|u| !u.trim().is_empty()) {
+            match list_personal_leave(user_id) {
+                Ok(leave) => {
+                    calendar.leave = leave.iter().filter_map(|l| parse_date(&l.date).ok()).collect();
+                }
+                Err(e) => eprintln!("读取请假记录失败: {}", e),
+            }
+        }
+        calendar
+    }
+
+    pub fn day_info(&self, date: NaiveDate) -> DayInfo {
+        let (is_workday, kind, name) = if let Some(name) = self.holidays.get(&date) {
+            (false, "holiday", Some(name.clone()))
+        } else if self.leave.contains(&date) {
+            (false, "leave", None)
+        } else if let Some(name) = self.workdays.get(&date) {
+            (true, "makeup_workday", Some(name.clone()))
+        } else if date.weekday().number_from_monday() > 5 {
+            (false, "weekend", None)
+        } else {
+            (true, "workday", None)
+        };
+        DayInfo {
+            date: date.format(DATE_FORMAT).to_string(),
+            is_workday,
+            kind: kind.to_string(),
+            name,
+        }
+    }
+
+    /// 当天是否需要上班（需要写日报）
+    pub fn is_workday(&self, date: NaiveDate) -> bool {
+        self.day_info(date).is_workday
+    }
+
+    /// 严格早于 `date` 的最近一个工作日，一年内找不到时返回前一天
+    pub fn previous_workday(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date - Duration::days(1);
+        for _ in 0..366 {
+            if self.is_workday(day) {
+                return day;
+            }
+            day -= Duration::days(1);
+        }
+        date - Duration::days(1)
+    }
+
+    /// 严格晚于 `date` 的最近一个工作日，一年内找不到时返回后一天
+    pub fn next_workday(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date + Duration::days(1);
+        for _ in 0..366 {
+            if self.is_workday(day) {
+                return day;
+            }
+            day += Duration::days(1);
+        }
+        date + Duration::days(1)
+    }
+
+    /// 区间内（含首尾）的所有工作日
+    pub fn workdays_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let mut days = Vec::new();
+        let mut date = start;
+        while date <= end {
+            if self.is_workday(date) {
+                days.push(date);
+            }
+            date += Duration::days(1);
+        }
+        days
+    }
+}
+
+/// 包含 `date` 的自然周（周一至周日）
+pub fn week_range(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    (monday, monday + Duration::days(6))
+}
+
+/// "2024-04" 所在月份的第一天和最后一天
+pub fn month_range(year_month: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", year_month.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("月份格式不正确: {}, 应为YYYY-MM格式", year_month))?;
+    let next_month = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    }
+    .ok_or("月份超出范围")?;
+    Ok((first, next_month - Duration::days(1)))
+}
+
+pub fn list_calendar_days(start_date: &str, end_date: &str, user_id: Option<&str>) -> Result<Vec<DayInfo>, String> {
+    let start = parse_date(start_date)?;
+    let end = parse_date(end_date)?;
+    let calendar = WorkCalendar::for_user(user_id);
+
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        days.push(calendar.day_info(date));
+        date += Duration::days(1);
+    }
+    Ok(days)
+}
+
+pub fn get_holiday_data() -> HolidayData {
+    load_override_data()
+}
+
+/// 保存用户更新的节假日数据（比如每年年底发布的新一年安排）
+pub fn save_holiday_data(data: HolidayData) -> Result<(), String> {
+    for range in &data.holidays {
+        if parse_date(&range.start)? > parse_date(&range.end)? {
+            return Err(format!("假期 {} 的开始日期晚于结束日期", range.name));
+        }
+    }
+    for day in &data.workdays {
+        parse_date(&day.date)?;
+    }
+    write_override_data(&data)
+}
+
+// ICS日期形如 20250101 或 20250101T000000
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(0..8)?, "%Y%m%d").ok()
+}
+
+/// 解析节假日ICS日历，标题中含"班"的事件视为调休上班日，其余视为放假
+pub fn parse_holiday_ics(content: &str) -> HolidayData {
+    // 先展开折行：以空格或制表符开头的行属于上一行
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&raw[1..]);
+            }
+        } else {
+            lines.push(raw.trim_end_matches('\r').to_string());
+        }
+    }
+
+    let mut data = HolidayData::default();
+    let mut summary = String::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    for line in &lines {
+        let (key, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        // 去掉 DTSTART;VALUE=DATE 这类参数
+        let key = key.split(';').next().unwrap_or(key);
+        match key {
+            "BEGIN" if value == "VEVENT" => {
+                summary.clear();
+                start = None;
+                end = None;
+            }
+            "SUMMARY" => summary = value.trim().to_string(),
+            "DTSTART" => start = parse_ics_date(value),
+            "DTEND" => end = parse_ics_date(value),
+            "END" if value == "VEVENT" => {
+                let start = match start {
+                    Some(start) => start,
+                    None => continue,
+                };
+                // DTEND 不包含在事件内
+                let last = end.map(|e| e - Duration::days(1)).filter(|e| *e >= start).unwrap_or(start);
+                if summary.contains('班') {
+                    let mut date = start;
+                    while date <= last {
+                        data.workdays.push(MakeupWorkday {
+                            name: summary.clone(),
+                            date: date.format(DATE_FORMAT).to_string(),
+                        });
+                        date += Duration::days(1);
+                    }
+                } else {
+                    data.holidays.push(HolidayRange {
+                        name: summary.clone(),
+                        start: start.format(DATE_FORMAT).to_string(),
+                        end: last.format(DATE_FORMAT).to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    data
+}
+
+/// 从ICS文件导入节假日数据并合并到用户数据中，返回导入的事件数
+pub fn import_holiday_ics(path: &str) -> Result<usize, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("读取ICS文件失败: {}", e))?;
+    let imported = parse_holiday_ics(&content);
+    let count = imported.holidays.len() + imported.workdays.len();
+    if count == 0 {
+        return Err("ICS文件中没有找到节假日事件".to_string());
+    }
+
+    let mut data = load_override_data();
+    let imported_workdays: HashSet<String> = imported.workdays.iter().map(|d| d.date.clone()).collect();
+    data.holidays.retain(|h| !imported.holidays.iter().any(|i| i.start == h.start && i.end == h.end));
+    data.workdays.retain(|d| !imported_workdays.contains(&d.date));
+    data.holidays.extend(imported.holidays);
+    data.workdays.extend(imported.workdays);
+    write_override_data(&data)?;
+    Ok(count)
+}
+
+pub fn list_personal_leave(user_id: &str) -> Result<Vec<PersonalLeave>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT user_id, date, reason FROM personal_leave WHERE user_id = ?1 ORDER BY date ASC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([user_id], |row| {
+        Ok(PersonalLeave {
+            user_id: row.get(0)?,
+            date: row.get(1)?,
+            reason: row.get(2)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn add_personal_leave(leave: PersonalLeave) -> Result<(), String> {
+    parse_date(&leave.date)?;
+    let conn = open_db()?;
+    conn.execute(
+        "INSERT INTO personal_leave (user_id, date, reason) VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id, date) DO UPDATE SET reason = excluded.reason",
+        params![leave.user_id, leave.date, leave.reason],
+    ).map_err(|e| format!("保存请假记录失败: {}", e))?;
+    Ok(())
+}
+
+pub fn remove_personal_leave(user_id: &str, date: &str) -> Result<(), String> {
+    let conn = open_db()?;
+    conn.execute(
+        "DELETE FROM personal_leave WHERE user_id = ?1 AND date = ?2",
+        params![user_id, date],
+    ).map_err(|e| format!("删除请假记录失败: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// Calendar module - 法定节假日、调休与个人请假
+mod calendar_impl;
+
+pub use calendar_impl::{WorkCalendar, DayInfo, HolidayData, PersonalLeave, month_range, week_range};
+
+// Re-export with #[tauri::command] preservation
+#[tauri::command]
+pub fn list_calendar_days(start_date: String, end_date: String, user_id: Option<String>) -> Result<Vec<DayInfo>, String> {
+    calendar_impl::list_calendar_days(&start_date, &end_date, user_id.as_deref())
+}
+
+#[tauri::command]
+pub fn get_holiday_data() -> HolidayData {
+    calendar_impl::get_holiday_data()
+}
+
+#[tauri::command]
+pub fn save_holiday_data(data: HolidayData) -> Result<(), String> {
+    calendar_impl::save_holiday_data(data)
+}
+
+#[tauri::command]
+pub fn import_holiday_ics(path: String) -> Result<usize, String> {
+    calendar_impl::import_holiday_ics(&path)
+}
+
+#[tauri::command]
+pub fn list_personal_leave(user_id: String) -> Result<Vec<PersonalLeave>, String> {
+    calendar_impl::list_personal_leave(&user_id)
+}
+
+#[tauri::command]
+pub fn add_personal_leave(leave: PersonalLeave) -> Result<(), String> {
+    calendar_impl::add_personal_leave(leave)
+}
+
+#[tauri::command]
+pub fn remove_personal_leave(user_id: String, date: String) -> Result<(), String> {
+    calendar_impl::remove_personal_leave(&user_id, &date)
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, HashSet};
+use chrono::{Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::database::calendar::WorkCalendar;
+use crate::database::sqlite::{init_db, load_dailies_between};
+use crate::database::supabase::get_submission_dates_from_supabase;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// 单次查询的最大天数，避免误传参数时生成过大的结果
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// 某一天的日报填写情况
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageDay {
+    pub date: String,
+    pub status: String, // filled / missing / pending / leave / holiday / weekend / future
+    pub name: Option<String>, // 节假日或调休名称
+}
+
+/// 一个用户在日期范围内的日报覆盖情况
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReportCoverage {
+    pub user_id: Option<String>,
+    pub start_date: String,
+    pub end_date: String,
+    pub days: Vec<CoverageDay>,
+    pub workdays: usize,
+    pub filled: usize,
+    pub missing: usize,
+    pub missing_dates: Vec<String>,
+    pub coverage_rate: f64,   // 已填写工作日 / 截至今天的工作日
+    pub current_streak: usize, // 截至最近一个工作日连续填写的工作日数
+    pub longest_streak: usize,
+}
+
+/// 团队成员的日报覆盖情况
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TeamCoverage {
+    pub start_date: String,
+    pub end_date: String,
+    pub members: Vec<ReportCoverage>,
+    pub missing_members: Vec<String>, // 范围内有缺报的成员
+}
+
+fn parse_range(start_date: &str, end_date: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = NaiveDate::parse_from_str(start_date, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", start_date))?;
+    let end = NaiveDate::parse_from_str(end_date, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", end_date))?;
+    if start > end {
+        return Err("开始日期不能晚于结束日期".to_string());
+    }
+    if end - start > Duration::days(MAX_RANGE_DAYS) {
+        return Err(format!("查询范围不能超过{}天", MAX_RANGE_DAYS));
+    }
+    Ok((start, end))
+}
+
+/// 根据工作日历和已填写的日期计算覆盖情况
+///
+/// 今天还没填写时记为 pending，不算缺报也不打断连续记录；非工作日不影响连续记录
+pub fn compute_coverage(
+    calendar: &WorkCalendar,
+    filled_dates: &HashSet<String>,
+    user_id: Option<String>,
+    start: NaiveDate,
+    end: NaiveDate,
+    today: NaiveDate,
+) -> ReportCoverage {
+    let mut days = Vec::new();
+    let mut workdays = 0;
+    let mut filled = 0;
+    let mut missing_dates = Vec::new();
+    let mut streak = 0;
+    let mut longest_streak = 0;
+
+    let mut date = start;
+    while date <= end {
+        let info = calendar.day_info(date);
+        let is_filled = filled_dates.contains(&info.date);
+        let status = if date > today {
+            "future"
+        } else if !info.is_workday {
+            if is_filled { "filled" } else { info.kind.as_str() }
+        } else if is_filled {
+            "filled"
+        } else if date == today {
+            "pending"
+        } else {
+            "missing"
+        };
+
+        if info.is_workday && date <= today {
+            match status {
+                "filled" => {
+                    workdays += 1;
+                    filled += 1;
+                    streak += 1;
+                    longest_streak = longest_streak.max(streak);
+                }
+                "missing" => {
+                    workdays += 1;
+                    missing_dates.push(info.date.clone());
+                    streak = 0;
+                }
+                _ => {}
+            }
+        }
+
+        days.push(CoverageDay {
+            date: info.date,
+            status: status.to_string(),
+            name: info.name,
+        });
+        date += Duration::days(1);
+    }
+
+    ReportCoverage {
+        user_id,
+        start_date: start.format(DATE_FORMAT).to_string(),
+        end_date: end.format(DATE_FORMAT).to_string(),
+        days,
+        workdays,
+        filled,
+        missing: missing_dates.len(),
+        missing_dates,
+        coverage_rate: if workdays == 0 { 1.0 } else { filled as f64 / workdays as f64 },
+        current_streak: streak,
+        longest_streak,
+    }
+}
+
+/// 查询本地日报的覆盖情况
+pub fn get_report_coverage(user_id: Option<String>, start_date: &str, end_date: &str) -> Result<ReportCoverage, String> {
+    let (start, end) = parse_range(start_date, end_date)?;
+    let conn = init_db().map_err(|e| e.to_string())?;
+    let filled_dates: HashSet<String> = load_dailies_between(&conn, start_date, end_date, user_id.as_deref())?
+        .into_iter()
+        .filter(|r| {
+            !(r.should_complete.trim().is_empty() && r.completed.trim().is_empty() && r.uncompleted.trim().is_empty())
+        })
+        .map(|r| r.date)
+        .collect();
+
+    let calendar = WorkCalendar::for_user(user_id.as_deref());
+    Ok(compute_coverage(&calendar, &filled_dates, user_id, start, end, Local::now().date_naive()))
+}
+
+/// 查询Supabase上团队成员的覆盖情况
+///
+/// `members` 为空时使用范围内提交过日报的所有用户；传入成员列表才能发现整段时间都没有提交的人
+pub async fn get_team_report_coverage(
+    start_date: &str,
+    end_date: &str,
+    members: Option<Vec<String>>,
+) -> Result<TeamCoverage, String> {
+    let (start, end) = parse_range(start_date, end_date)?;
+    let submissions = get_submission_dates_from_supabase(start_date, end_date).await?;
+
+    let mut by_user: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    if let Some(members) = &members {
+        for member in members {
+            by_user.entry(member.clone()).or_default();
+        }
+    }
+    for (user_id, date) in submissions {
+        if members.is_none() || by_user.contains_key(&user_id) {
+            by_user.entry(user_id).or_default().insert(date);
+        }
+    }
+
+    let today = Local::now().date_naive();
+    let members: Vec<ReportCoverage> = by_user
+        .into_iter()
+        .map(|(user_id, dates)| {
+            let calendar = WorkCalendar::for_user(Some(&user_id));
+            compute_coverage(&calendar, &dates, Some(user_id), start, end, today)
+        })
+        .collect();
+    let missing_members = members
+        .iter()
+        .filter(|m| m.missing > 0)
+        .filter_map(|m| m.user_id.clone())
+        .collect();
+
+    Ok(TeamCoverage {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        members,
+        missing_members,
+    })
+}
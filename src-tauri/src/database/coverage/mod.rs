@@ -0,0 +1,19 @@
+// Coverage module - 缺报检测与日报填写统计
+mod coverage_impl;
+
+pub use coverage_impl::{ReportCoverage, TeamCoverage};
+
+// Re-export with #[tauri::command] preservation
+#[tauri::command]
+pub fn get_report_coverage(user_id: Option<String>, start_date: String, end_date: String) -> Result<ReportCoverage, String> {
+    coverage_impl::get_report_coverage(user_id, &start_date, &end_date)
+}
+
+#[tauri::command]
+pub async fn get_team_report_coverage(
+    start_date: String,
+    end_date: String,
+    members: Option<Vec<String>>,
+) -> Result<TeamCoverage, String> {
+    coverage_impl::get_team_report_coverage(&start_date, &end_date, members).await
+}
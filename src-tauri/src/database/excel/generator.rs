@@ -1,24 +1,55 @@
 use xlsxwriter::Workbook;
 use xlsxwriter::format;
-use tauri_plugin_dialog::DialogExt;
-use umya_spreadsheet::{reader, writer};
+use umya_spreadsheet::{reader, writer, Spreadsheet, Worksheet};
 use std::collections::HashMap;
+use std::path::Path;
 use crate::database::types::{Task, UserInfo, DailyReport};
 use crate::database::sqlite::init_db;
+use crate::database::tickets::TicketExtractor;
+use super::header::{HeaderDictionary, SEARCH_COLUMNS, SEARCH_ROWS};
+use super::mapping::{load_mapping, parse_range, sheet_names, CellPosition, Overflow, TableLayout, TableMapping, TemplateMapping};
+use super::rows::{detect_table_end, expand_table_formulas, insert_table_rows};
+use super::values::{match_option, typed_value, validation_list, write_typed, CellValue};
+use super::sheets::{new_sheet_name, select_sheet, SheetExportOptions};
+use super::output::{deliver, ExportFile, ExportOutput, ExportSink, XLSX_CONTENT_TYPE};
 
-pub fn generate_excel_report(
-    app_handle: tauri::AppHandle,
-    start_date: String,
-    end_date: String,
+/// 周报Excel的默认文件名
+pub fn weekly_file_name(start_date: &str, end_date: &str) -> String {
+    format!("周报_{}_至_{}.xlsx", start_date, end_date)
+}
+
+/// 生成周报Excel并交给指定的输出方式，不依赖界面，定时任务也可以直接调用
+pub fn export_weekly_excel(
+    start_date: &str,
+    end_date: &str,
     tasks: Vec<Task>,
-    next_week_plan: String,
-) -> Result<(), String> {
-    // 文件名建议
-    let suggested_filename = format!("周报_{}_至_{}.xlsx", start_date, end_date);
-    
-    // 创建临时文件目录，但不让它自动删除
-    let temp_file_path = std::env::temp_dir().join(&suggested_filename);
-    let temp_path_str = temp_file_path.to_str().expect("路径转换失败").to_string();
+    next_week_plan: &str,
+    sink: &ExportSink,
+) -> Result<ExportOutput, String> {
+    let data = build_weekly_workbook(start_date, end_date, tasks, next_week_plan)?;
+    let file = ExportFile {
+        kind: "weekly".to_string(),
+        title: format!("周报 {} 至 {}", start_date, end_date),
+        file_name: weekly_file_name(start_date, end_date),
+        content_type: XLSX_CONTENT_TYPE.to_string(),
+        data,
+    };
+    deliver(file, sink)
+}
+
+/// 生成周报Excel文件的内容
+///
+/// xlsxwriter 只能写入文件，因此先写到独立的临时目录中再读回，临时目录在返回时删除，多次导出互不影响
+pub fn build_weekly_workbook(
+    start_date: &str,
+    end_date: &str,
+    tasks: Vec<Task>,
+    next_week_plan: &str,
+) -> Result<Vec<u8>, String> {
+    let temp_dir = tempfile::Builder::new().prefix("weekly_report").tempdir()
+        .map_err(|e| format!("无法创建临时目录: {}", e))?;
+    let temp_path = temp_dir.path().join("weekly.xlsx");
+    let temp_path_str = temp_path.to_str().ok_or("路径转换失败")?.to_string();
     
     // 创建Excel文件
     let workbook = Workbook::new(&temp_path_str)
@@ -127,6 +158,11 @@ pub fn generate_excel_report(
         "1"
     };
     
+    // 补全任务编号，编号可映射到问题跟踪系统时写为超链接
+    let extractor = TicketExtractor::from_config();
+    let mut tasks = tasks;
+    extractor.fill_task_ids(&mut tasks);
+    
     // 写入任务内容
     for (i, task) in tasks.iter().enumerate() {
         let row = i as u32 + header_row + 1;
@@ -147,8 +183,15 @@ pub fn generate_excel_report(
         
         // 任务编号
         if let Some(task_id) = &task.task_id {
-            sheet.write_string(row, 1, task_id, Some(&content_format))
-                .map_err(|e| format!("写入任务编号失败: {}", e))?;
+            let first_key = task_id.split(',').next().unwrap_or_default().trim();
+            if let Some(url) = extractor.url_for(first_key) {
+                let formula = format!("=HYPERLINK(\"{}\",\"{}\")", url.replace('"', "\"\""), task_id.replace('"', "\"\""));
+                sheet.write_formula(row, 1, &formula, Some(&content_format))
+                    .map_err(|e| format!("写入任务编号失败: {}", e))?;
+            } else {
+                sheet.write_string(row, 1, task_id, Some(&content_format))
+                    .map_err(|e| format!("写入任务编号失败: {}", e))?;
+            }
         } else {
             sheet.write_string(row, 1, &format!("任务{:03}", i+1), Some(&content_format))
                 .map_err(|e| format!("写入任务编号失败: {}", e))?;
@@ -177,7 +220,7 @@ pub fn generate_excel_report(
             sheet.write_string(row, 4, time, Some(&date_format))
                 .map_err(|e| format!("写入计划开始时间失败: {}", e))?;
         } else {
-            sheet.write_string(row, 4, &start_date, Some(&date_format))
+            sheet.write_string(row, 4, start_date, Some(&date_format))
                 .map_err(|e| format!("写入计划开始时间失败: {}", e))?;
         }
         
@@ -186,7 +229,7 @@ pub fn generate_excel_report(
             sheet.write_string(row, 5, time, Some(&date_format))
                 .map_err(|e| format!("写入计划结束时间失败: {}", e))?;
         } else {
-            sheet.write_string(row, 5, &end_date, Some(&date_format))
+            sheet.write_string(row, 5, end_date, Some(&date_format))
                 .map_err(|e| format!("写入计划结束时间失败: {}", e))?;
         }
         
@@ -230,173 +273,336 @@ pub fn generate_excel_report(
         .map_err(|e| format!("写入下周计划标题失败: {}", e))?;
     
     if !next_week_plan.is_empty() {
-        sheet.merge_range(next_row + 2, 0, next_row + 2, 10, &next_week_plan, Some(&content_format))
+        sheet.merge_range(next_row + 2, 0, next_row + 2, 10, next_week_plan, Some(&content_format))
             .map_err(|e| format!("写入下周计划内容失败: {}", e))?;
     }
     
     // 关闭工作簿，写入临时文件
     workbook.close().map_err(|e| format!("保存Excel文件失败: {}", e))?;
     
-    // 使用tauri-plugin-dialog进行文件保存对话框
-    let dialog = app_handle.dialog();
-    dialog.file()
-        .add_filter("Excel文件", &["xlsx"])
-        .set_file_name(&suggested_filename)
-        .save_file(move |path_opt| {
-            if let Some(path) = path_opt {
-                // 将临时文件复制到用户选择的位置
-                if let Err(e) = std::fs::copy(&temp_path_str, path.to_string()) {
-                    eprintln!("保存文件失败: {}", e);
-                } else {
-                    // 删除临时文件
-                    let _ = std::fs::remove_file(&temp_path_str);
-                }
-            }
-        });
-    
-    Ok(())
+    std::fs::read(&temp_path).map_err(|e| format!("读取生成的Excel文件失败: {}", e))
 }
 
-pub fn export_with_template(
-    template_path: String,
-    output_path: String,
-    tasks: Vec<Task>,
-    next_week_plan: String,
-) -> Result<(), String> {
-    // 1. 打开模板
-    let mut book = reader::xlsx::read(&template_path)
-        .map_err(|e| format!("读取模板失败: {}", e))?;
-    let sheet_name = book.get_sheet_collection().get(0).unwrap().get_name().to_string();
-    let sheet = book.get_sheet_by_name_mut(&sheet_name).ok_or("找不到工作表")?;
+// 任务各字段的文字，键与映射文件中的字段名一致
+fn task_values(task: &Task) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("task", task.task.clone()),
+        ("task_id", task.task_id.clone().unwrap_or_default()),
+        ("task_name", task.task_name.clone().unwrap_or_default()),
+        ("plan_start_time", task.plan_start_time.clone().unwrap_or_default()),
+        ("plan_end_time", task.plan_end_time.clone().unwrap_or_default()),
+        ("actual_start_time", task.actual_start_time.clone().unwrap_or_default()),
+        ("actual_end_time", task.actual_end_time.clone().unwrap_or_default()),
+        ("status", task.status.clone()),
+        ("plan_hours", task.plan_hours.clone().unwrap_or_default()),
+        ("actual_hours", task.actual_hours.clone().unwrap_or_default()),
+        ("remarks", task.remarks.clone()),
+    ])
+}
 
-    // 2. 查找表头行和列索引
-    let mut col_map = HashMap::new();
-    let mut header_row = 0u32;
-    'outer: for row in 1..=20 {
-        for col in 1..=20 {
-            let cell = sheet.get_cell((col, row));
-            if let Some(cell) = cell {
-                let text = cell.get_value();
-                if text.contains("任务内容") || text.contains("工作内容") {
-                    col_map.insert("task", col);
-                }
-                if text.contains("任务编号") || text.contains("编号") {
-                    col_map.insert("task_id", col);
-                }
-                if text.contains("任务名称") || text.contains("名称") {
-                    col_map.insert("task_name", col);
-                }
-                if text.contains("计划开始") {
-                    col_map.insert("plan_start_time", col);
-                }
-                if text.contains("计划结束") {
-                    col_map.insert("plan_end_time", col);
-                }
-                if text.contains("实际开始") {
-                    col_map.insert("actual_start_time", col);
-                }
-                if text.contains("实际结束") {
-                    col_map.insert("actual_end_time", col);
-                }
-                if text.contains("任务状态") || text.contains("状态") {
-                    col_map.insert("status", col);
-                }
-                if text.contains("计划工时") {
-                    col_map.insert("plan_hours", col);
-                }
-                if text.contains("实际工时") {
-                    col_map.insert("actual_hours", col);
-                }
-                if text.contains("备注") {
-                    col_map.insert("remarks", col);
-                }
-            }
+// 月报中一天日报对应的一行
+fn daily_values(report: &DailyReport) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("date", report.date.clone()),
+        ("task", report.should_complete.clone()),
+        ("should_complete", report.should_complete.clone()),
+        ("completed", report.completed.clone()),
+        ("uncompleted", report.uncompleted.clone()),
+        ("status", "已完成".to_string()),
+        ("remarks", report.remarks.clone()),
+    ])
+}
+
+// 写入表格的结果，插入了新行时表格下方的单元格相应下移
+struct TableWrite {
+    written: usize,
+    // 插入的行数和插入位置，原来在插入位置及其下方的单元格下移 inserted 行
+    inserted: u32,
+    insert_at: Option<u32>,
+    end_row: Option<u32>,
+    warnings: Vec<String>,
+}
+
+impl TableWrite {
+    // 插入新行后，原位置单元格现在的位置
+    fn shift(&self, (col, row): CellPosition) -> CellPosition {
+        match self.insert_at {
+            Some(insert_at) if row >= insert_at => (col, row + self.inserted),
+            _ => (col, row),
         }
-        if col_map.len() >= 3 {
-            header_row = row;
-            break 'outer;
+    }
+}
+
+// 写入映射中的命名单元格，没有对应值的单元格保持不变
+fn write_cells(
+    sheet: &mut Worksheet,
+    mapping: &TemplateMapping,
+    values: &HashMap<&str, String>,
+    table: Option<&TableWrite>,
+) -> Result<(), String> {
+    for (name, position) in mapping.cell_positions()? {
+        if let Some(value) = values.get(name.as_str()) {
+            let position = table.map_or(position, |t| t.shift(position));
+            sheet.get_cell_mut(position).set_value(value.clone());
         }
     }
-    if header_row == 0 {
-        return Err("未能识别模板表头，请检查模板格式".to_string());
+    Ok(())
+}
+
+// 按表格区域逐行写入，任务超出表格时按 overflow 处理
+fn write_table(sheet: &mut Worksheet, layout: &TableLayout, rows: &[HashMap<&str, String>]) -> Result<TableWrite, String> {
+    // 结束行为开始行的上一行表示表格没有数据行
+    if layout.end_row.is_some_and(|end| end + 1 < layout.start_row) {
+        return Err("模板表格的结束行不能小于开始行".to_string());
     }
+    let mut inserted = 0;
+    let mut insert_at = None;
+    let count = match (layout.capacity(), layout.end_row) {
+        (Some(capacity), Some(end_row)) if rows.len() > capacity => match layout.overflow {
+            Overflow::Insert if capacity == 0 => {
+                // 表头下方紧接着收尾内容：在收尾内容之前插入全部行，样式沿用表头行
+                inserted = rows.len() as u32;
+                insert_at = Some(layout.start_row);
+                insert_table_rows(sheet, layout.start_row, inserted, end_row);
+                rows.len()
+            }
+            Overflow::Insert => {
+                // 在最后一行之前插入，表格下方的内容和引用整个表格的公式随之下移和扩展
+                inserted = (rows.len() - capacity) as u32;
+                insert_at = Some(end_row);
+                insert_table_rows(sheet, end_row, inserted, end_row);
+                expand_table_formulas(sheet, layout.start_row, end_row, end_row + inserted);
+                rows.len()
+            }
+            Overflow::Extend => rows.len(),
+            Overflow::Truncate => {
+                eprintln!("模板表格只能容纳 {} 行，忽略了 {} 个任务", capacity, rows.len() - capacity);
+                capacity
+            }
+            Overflow::Error => {
+                return Err(format!("任务数量 {} 超出模板表格容量 {}", rows.len(), capacity));
+            }
+        },
+        _ => rows.len(),
+    };
 
-    // 3. 批量写入任务内容（假设数据区紧跟表头行）
-    for (i, task) in tasks.iter().enumerate() {
-        let row = header_row + 1 + i as u32;
-        if let Some(&col) = col_map.get("task") {
-            sheet.get_cell_mut((col, row)).set_value(&task.task);
-        }
-        if let Some(&col) = col_map.get("task_id") {
-            sheet.get_cell_mut((col, row)).set_value(task.task_id.clone().unwrap_or_default());
-        }
-        if let Some(&col) = col_map.get("task_name") {
-            sheet.get_cell_mut((col, row)).set_value(task.task_name.clone().unwrap_or_default());
-        }
-        if let Some(&col) = col_map.get("plan_start_time") {
-            sheet.get_cell_mut((col, row)).set_value(task.plan_start_time.clone().unwrap_or_default());
-        }
-        if let Some(&col) = col_map.get("plan_end_time") {
-            sheet.get_cell_mut((col, row)).set_value(task.plan_end_time.clone().unwrap_or_default());
-        }
-        if let Some(&col) = col_map.get("actual_start_time") {
-            sheet.get_cell_mut((col, row)).set_value(task.actual_start_time.clone().unwrap_or_default());
+    // 状态列有下拉列表时，状态需要是其中的选项
+    let status_options = layout
+        .columns
+        .iter()
+        .find(|(field, _)| field == "status")
+        .and_then(|(_, col)| validation_list(sheet, (*col, layout.start_row)));
+
+    let mut warnings = Vec::new();
+    for (i, values) in rows.iter().take(count).enumerate() {
+        let row = layout.start_row + i as u32;
+        for (field, col) in &layout.columns {
+            let Some(value) = values.get(field.as_str()) else {
+                continue;
+            };
+            let value = match (field.as_str(), &status_options) {
+                ("status", Some(options)) if !value.trim().is_empty() => match match_option(value, options) {
+                    Some(option) => CellValue::Text(option),
+                    None => {
+                        warnings.push(format!("第 {} 行的状态 \"{}\" 不在模板下拉列表中（{}）", row, value, options.join("、")));
+                        CellValue::Text(value.clone())
+                    }
+                },
+                _ => typed_value(field, value),
+            };
+            write_typed(sheet.get_cell_mut((*col, row)), &value);
         }
-        if let Some(&col) = col_map.get("actual_end_time") {
-            sheet.get_cell_mut((col, row)).set_value(task.actual_end_time.clone().unwrap_or_default());
+    }
+    for warning in &warnings {
+        eprintln!("{}", warning);
+    }
+    Ok(TableWrite { written: count, inserted, insert_at, end_row: layout.end_row, warnings })
+}
+
+// 工作表左上角区域的单元格文字，合并单元格的每一格都填入左上角的值
+fn sheet_grid(sheet: &Worksheet) -> Vec<Vec<String>> {
+    let mut grid: Vec<Vec<String>> = (1..=SEARCH_ROWS as u32)
+        .map(|row| (1..=SEARCH_COLUMNS as u32).map(|col| sheet.get_value((col, row))).collect())
+        .collect();
+    for merged in sheet.get_merge_cells() {
+        let Ok(((start_col, start_row), (end_col, end_row))) = parse_range(&merged.get_range()) else {
+            continue;
+        };
+        let value = sheet.get_value((start_col, start_row));
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                let cell = grid
+                    .get_mut(row as usize - 1)
+                    .and_then(|cells| cells.get_mut(col as usize - 1));
+                if let Some(cell) = cell {
+                    cell.clone_from(&value);
+                }
+            }
         }
-        if let Some(&col) = col_map.get("status") {
-            sheet.get_cell_mut((col, row)).set_value(&task.status);
+    }
+    grid
+}
+
+// 没有映射文件时按表头文字查找各列
+fn detect_table(sheet: &Worksheet) -> Result<TableLayout, String> {
+    let detection = HeaderDictionary::from_config()
+        .detect(&sheet_grid(sheet))
+        .ok_or("未能识别模板表头，请检查模板格式")?;
+    if detection.confidence < 0.8 {
+        eprintln!("模板表头识别可信度较低: {:.2}", detection.confidence);
+    }
+
+    // 数据区紧跟表头行，到下方的收尾内容（如"下周计划"、"合计"）之前为止
+    let start_row = detection.header_row as u32 + 2;
+    let columns: Vec<(String, u32)> = detection
+        .columns
+        .iter()
+        .map(|m| (m.field.clone(), m.column as u32 + 1))
+        .collect();
+    let last_column = columns.iter().map(|(_, col)| *col).max().unwrap_or(1);
+    Ok(TableLayout {
+        start_row,
+        end_row: detect_table_end(sheet, start_row, last_column),
+        columns,
+        overflow: Overflow::Insert,
+    })
+}
+
+// 映射文件中的表格区域，没有设置 end_row 时按表格下方的收尾内容查找结束行
+fn mapped_layout(sheet: &Worksheet, table: &TableMapping) -> Result<TableLayout, String> {
+    let mut layout = table.layout()?;
+    if layout.end_row.is_none() {
+        let last_column = layout.columns.iter().map(|(_, col)| *col).max().unwrap_or(1);
+        layout.end_row = detect_table_end(sheet, layout.start_row, last_column);
+    }
+    Ok(layout)
+}
+
+// 把任务和下周计划写入模板中的一个工作表
+fn fill_weekly_sheet(
+    sheet: &mut Worksheet,
+    mapping: Option<&TemplateMapping>,
+    tasks: &[Task],
+    next_week_plan: &str,
+) -> Result<Vec<String>, String> {
+    // 确定表格区域：优先使用映射，否则按表头识别
+    let layout = match mapping.and_then(|m| m.table.as_ref()) {
+        Some(table) => mapped_layout(sheet, table)?,
+        None if mapping.is_some() => return Err("映射文件中没有定义表格".to_string()),
+        None => detect_table(sheet)?,
+    };
+
+    // 批量写入任务内容
+    let extractor = TicketExtractor::from_config();
+    let mut tasks = tasks.to_vec();
+    extractor.fill_task_ids(&mut tasks);
+    let rows: Vec<_> = tasks.iter().map(task_values).collect();
+    let table = write_table(sheet, &layout, &rows)?;
+
+    // 任务编号可映射到问题跟踪系统时加上超链接
+    if let Some((_, col)) = layout.columns.iter().find(|(field, _)| field == "task_id") {
+        for (i, task) in tasks.iter().take(table.written).enumerate() {
+            let url = task.task_id.as_deref()
+                .and_then(|id| id.split(',').next())
+                .and_then(|key| extractor.url_for(key.trim()));
+            if let Some(url) = url {
+                sheet.get_cell_mut((*col, layout.start_row + i as u32)).get_hyperlink_mut().set_url(url);
+            }
         }
-        if let Some(&col) = col_map.get("plan_hours") {
-            sheet.get_cell_mut((col, row)).set_value(task.plan_hours.clone().unwrap_or_default());
+    }
+
+    // 写入下周计划
+    match mapping {
+        Some(mapping) => {
+            let values = HashMap::from([("next_week_plan", next_week_plan.to_string())]);
+            write_cells(sheet, mapping, &values, Some(&table))?;
         }
-        if let Some(&col) = col_map.get("actual_hours") {
-            sheet.get_cell_mut((col, row)).set_value(task.actual_hours.clone().unwrap_or_default());
+        None => {
+            // 从表格结束处查找"下周计划"关键字，写到下方一行
+            let end = match table.end_row {
+                Some(end_row) => end_row + table.inserted + 1,
+                None => layout.start_row + table.written as u32,
+            };
+            for row in end..=(end + 9) {
+                for col in 1..=20 {
+                    let cell = sheet.get_cell_mut((col, row));
+                    if cell.get_value().contains("下周") && cell.get_value().contains("计划") {
+                        sheet.get_cell_mut((col, row + 1)).set_value(next_week_plan);
+                        break;
+                    }
+                }
+            }
         }
-        if let Some(&col) = col_map.get("remarks") {
-            sheet.get_cell_mut((col, row)).set_value(&task.remarks);
+    }
+    Ok(table.warnings)
+}
+
+// 把工作表放入工作簿，已有同名工作表时原位替换
+fn put_sheet(book: &mut Spreadsheet, sheet: Worksheet) -> Result<(), String> {
+    let existing = book
+        .get_sheet_collection()
+        .iter()
+        .position(|s| s.get_name() == sheet.get_name());
+    match existing {
+        Some(index) => book.get_sheet_collection_mut()[index] = sheet,
+        None => {
+            book.add_sheet(sheet).map_err(|e| format!("添加工作表失败: {}", e))?;
         }
     }
+    Ok(())
+}
 
-    // 4. 查找"下周计划"关键字并写入内容
-    for row in (header_row + tasks.len() as u32 + 1)..=(header_row + tasks.len() as u32 + 10) {
-        for col in 1..=20 {
-            let cell = sheet.get_cell_mut((col, row));
-            if cell.get_value().contains("下周") && cell.get_value().contains("计划") {
-                // 写到下方一行
-                sheet.get_cell_mut((col, row + 1)).set_value(&next_week_plan);
-                break;
-            }
+pub fn export_with_template(
+    template_path: String,
+    output_path: String,
+    tasks: Vec<Task>,
+    next_week_plan: String,
+    options: SheetExportOptions,
+) -> Result<Vec<String>, String> {
+    // 1. 打开模板和模板旁边的映射文件
+    let mapping = load_mapping(&template_path)?;
+    let mut book = reader::xlsx::read(&template_path)
+        .map_err(|e| format!("读取模板失败: {}", e))?;
+    let pattern = options.sheet.as_deref().or(mapping.as_ref().and_then(|m| m.sheet.as_deref()));
+    let sheet_name = select_sheet(&sheet_names(&book), pattern)?;
+    let sheet = book.get_sheet_by_name_mut(&sheet_name).ok_or("找不到工作表")?;
+
+    // 2. 写入任务和下周计划，返回需要提示用户的问题（如状态不在下拉列表中）
+    let warnings = fill_weekly_sheet(sheet, mapping.as_ref(), &tasks, &next_week_plan)?;
+
+    // 3. 追加模式：填好的工作表改名后放入已有的输出文件，输出文件不存在时以模板为基础创建
+    if options.append {
+        let new_name = new_sheet_name(options.sheet_name.as_deref());
+        sheet.set_name(new_name);
+        if Path::new(&output_path).exists() {
+            let sheet = sheet.clone();
+            let mut target = reader::xlsx::read(&output_path)
+                .map_err(|e| format!("读取已有的周报文件失败: {}", e))?;
+            put_sheet(&mut target, sheet)?;
+            writer::xlsx::write(&target, &output_path).map_err(|e| format!("保存Excel失败: {}", e))?;
+            return Ok(warnings);
         }
     }
 
-    // 5. 保存
+    // 4. 保存
     writer::xlsx::write(&book, &output_path).map_err(|e| format!("保存Excel失败: {}", e))?;
-    Ok(())
+    Ok(warnings)
 }
 
 pub fn generate_monthly_report(
-    _app_handle: tauri::AppHandle,
     template_path: String,
     output_path: String,
     year_month: String, // 例如 "2024-04"
     user_info: UserInfo,
 ) -> Result<(), String> {
-    // 1. 读取模板
+    // 1. 读取模板，没有映射文件时使用原先约定的位置
+    let mapping = load_mapping(&template_path)?.unwrap_or_else(TemplateMapping::monthly_default);
     let mut book = reader::xlsx::read(&template_path)
         .map_err(|e| format!("读取模板失败: {}", e))?;
-    let sheet_name = book.get_sheet_collection().get(0).unwrap().get_name().to_string();
+    let sheet_name = mapping.sheet_name(&book)?;
     let sheet = book.get_sheet_by_name_mut(&sheet_name).ok_or("找不到工作表")?;
 
-    // 2. 填充表头（岗位/部门/姓名/时间）
-    // 假设表头分别在B2、D2、F2、H2
-    sheet.get_cell_mut((2, 2)).set_value(user_info.position.clone());
-    sheet.get_cell_mut((4, 2)).set_value(user_info.department.clone());
-    sheet.get_cell_mut((6, 2)).set_value(user_info.name.clone());
-    sheet.get_cell_mut((8, 2)).set_value(user_info.date.clone());
-
-    // 3. 查询本月所有日报
+    // 2. 查询本月所有日报
     let conn = init_db().map_err(|e| e.to_string())?;
     let mut stmt = conn.prepare(
         "SELECT date, content, should, done, undone FROM dailies WHERE date LIKE ?1 ORDER BY date ASC"
@@ -422,28 +628,35 @@ pub fn generate_monthly_report(
         reports.push(report.map_err(|e| e.to_string())?);
     }
 
-    // 4. 合成"本月工作总结"表格内容
-    // 假设表格从第6行开始，列顺序为：任务内容、状态、备注
-    let start_row = 6u32;
-    for (i, report) in reports.iter().enumerate() {
-        let row = start_row + i as u32;
-        sheet.get_cell_mut((1, row)).set_value(&report.should_complete); // 任务内容
-        sheet.get_cell_mut((2, row)).set_value("已完成"); // 状态
-        sheet.get_cell_mut((3, row)).set_value(&report.remarks); // 备注
-    }
+    // 3. 合成"本月工作总结"表格内容
+    let table = match &mapping.table {
+        Some(table) => {
+            let rows: Vec<_> = reports.iter().map(daily_values).collect();
+            let layout = mapped_layout(sheet, table)?;
+            Some(write_table(sheet, &layout, &rows)?)
+        }
+        None => None,
+    };
 
-    // 5. 合成"下月工作计划"
-    // 假设在第20行第1列
+    // 4. 合成"下月工作计划"，与表头（岗位/部门/姓名/时间）一起写入命名单元格
     let mut plan_text = String::new();
     for report in &reports {
         if !report.uncompleted.trim().is_empty() {
             plan_text.push_str(&report.uncompleted);
-            plan_text.push_str("\n");
+            plan_text.push('\n');
         }
     }
-    sheet.get_cell_mut((1, 20)).set_value(plan_text.trim());
+    let values = HashMap::from([
+        ("position", user_info.position.clone()),
+        ("department", user_info.department.clone()),
+        ("name", user_info.name.clone()),
+        ("date", user_info.date.clone()),
+        ("year_month", year_month.clone()),
+        ("next_month_plan", plan_text.trim().to_string()),
+    ]);
+    write_cells(sheet, &mapping, &values, table.as_ref())?;
 
-    // 6. 保存
+    // 5. 保存
     writer::xlsx::write(&book, &output_path).map_err(|e| format!("保存Excel失败: {}", e))?;
     Ok(())
-} 
\ No newline at end of file
+}
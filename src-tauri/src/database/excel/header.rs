@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+const SYNONYMS_FILE: &str = "header_synonyms.json";
+
+// 在表格左上角多大范围内查找表头
+pub const SEARCH_ROWS: usize = 20;
+pub const SEARCH_COLUMNS: usize = 20;
+// 至少识别出几列才认为是表头行
+const MIN_MATCHES: usize = 3;
+
+/// 内置的表头同义词，字段名与映射文件中的字段名一致
+fn default_synonyms() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![
+        ("task_id", vec!["任务编号", "编号", "ID", "任务ID", "工单号", "需求编号"]),
+        ("task_name", vec!["任务名称", "名称", "任务名"]),
+        ("task", vec!["任务内容", "内容", "工作内容", "量化指标", "任务描述", "工作事项", "事项"]),
+        ("plan_start_time", vec!["计划开始时间", "计划开始", "计划开始日期"]),
+        ("plan_end_time", vec!["计划结束时间", "计划结束", "计划完成时间", "计划结束日期", "计划完成日期"]),
+        ("actual_start_time", vec!["实际开始时间", "实际开始", "实际开始日期"]),
+        ("actual_end_time", vec!["实际结束时间", "实际结束", "实际完成时间", "实际结束日期", "实际完成日期"]),
+        ("status", vec!["任务状态", "状态", "完成状态", "完成情况"]),
+        ("plan_hours", vec!["计划工时", "预计工时"]),
+        ("actual_hours", vec!["实际工时"]),
+        ("remarks", vec!["备注", "说明"]),
+    ]
+}
+
+/// 读取 header_synonyms.json 中自定义的同义词（字段 -> 同义词列表）
+pub fn load_custom_synonyms() -> BTreeMap<String, Vec<String>> {
+    match fs::read_to_string(SYNONYMS_FILE) {
+        Ok(json) => match serde_json::from_str(&json) {
+            Ok(synonyms) => synonyms,
+            Err(e) => {
+                eprintln!("解析表头同义词失败，使用内置同义词: {}", e);
+                BTreeMap::new()
+            }
+        },
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+pub fn save_custom_synonyms(synonyms: &BTreeMap<String, Vec<String>>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(synonyms)
+        .map_err(|e| format!("序列化表头同义词失败: {}", e))?;
+    fs::write(SYNONYMS_FILE, json).map_err(|e| format!("保存表头同义词失败: {}", e))
+}
+
+// 统一全角括号、去掉空白、冒号和必填标记，英文转小写
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '*' | ':' | '：'))
+        .map(|c| match c {
+            '（' => '(',
+            '）' => ')',
+            _ => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+// 去掉末尾括号中的单位，例如 "计划工时(小时)"
+fn strip_unit(text: &str) -> &str {
+    match (text.rfind('('), text.ends_with(')')) {
+        (Some(start), true) if start > 0 => &text[..start],
+        _ => text,
+    }
+}
+
+// 一行表头的匹配结果，bool 表示是否用到了上一行的分组表头
+type RowMatches = Vec<(ColumnMatch, bool)>;
+
+/// 一列表头与字段的匹配结果，`column` 从0开始
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ColumnMatch {
+    pub field: String,
+    pub column: usize,
+    pub text: String,
+    pub score: f32,
+    // 完全等于某个同义词（忽略单位）时为 true，否则为包含匹配
+    pub exact: bool,
+}
+
+/// 表头识别结果，行列号均从0开始
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeaderDetection {
+    // 表头最后一行，数据从下一行开始
+    pub header_row: usize,
+    // 表头占用的行数，上方有合并的分组表头时为2
+    pub header_rows: usize,
+    pub columns: Vec<ColumnMatch>,
+    // 表头行中没有对应字段的列
+    pub unmatched: Vec<(usize, String)>,
+    // 各列匹配得分的平均值，0到1
+    pub confidence: f32,
+}
+
+impl HeaderDetection {
+    pub fn column(&self, field: &str) -> Option<usize> {
+        self.columns.iter().find(|m| m.field == field).map(|m| m.column)
+    }
+}
+
+/// 表头同义词词典，解析模板和按模板导出共用
+pub struct HeaderDictionary {
+    fields: Vec<(String, Vec<String>)>,
+}
+
+impl HeaderDictionary {
+    pub fn new(custom: BTreeMap<String, Vec<String>>) -> Self {
+        let mut fields: Vec<(String, Vec<String>)> = default_synonyms()
+            .into_iter()
+            .map(|(field, synonyms)| (field.to_string(), synonyms.into_iter().map(normalize).collect()))
+            .collect();
+        for (field, synonyms) in custom {
+            let synonyms = synonyms.iter().map(|s| normalize(s)).filter(|s| !s.is_empty());
+            match fields.iter_mut().find(|(f, _)| *f == field) {
+                Some((_, existing)) => existing.extend(synonyms),
+                None => fields.push((field, synonyms.collect())),
+            }
+        }
+        HeaderDictionary { fields }
+    }
+
+    /// 内置同义词加上 header_synonyms.json 中的自定义同义词
+    pub fn from_config() -> Self {
+        Self::new(load_custom_synonyms())
+    }
+
+    pub fn entries(&self) -> BTreeMap<String, Vec<String>> {
+        self.fields.iter().cloned().collect()
+    }
+
+    // 单元格文字与字段的最高得分：完全相同为1，去掉单位后相同为0.95，包含同义词时按同义词所占比例计分
+    fn score(&self, synonyms: &[String], text: &str) -> f32 {
+        let text = normalize(text);
+        if text.is_empty() {
+            return 0.0;
+        }
+        let stripped = strip_unit(&text);
+        let length = text.chars().count() as f32;
+        synonyms
+            .iter()
+            .map(|synonym| {
+                if *synonym == text {
+                    1.0
+                } else if synonym == stripped {
+                    0.95
+                } else if text.contains(synonym.as_str()) {
+                    0.5 + 0.4 * synonym.chars().count() as f32 / length
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f32::max)
+    }
+
+    // 以 row 为表头最后一行识别各列；上一行的分组表头（横向合并时只有第一格有字）与本行拼接后参与匹配
+    fn match_row(&self, grid: &[Vec<String>], row: usize) -> RowMatches {
+        let cells = &grid[row];
+        let above = row.checked_sub(1).map(|r| &grid[r]);
+        let mut group = String::new();
+
+        let mut candidates = Vec::new();
+        for (column, text) in cells.iter().enumerate() {
+            let text = text.trim();
+            let mut texts = vec![(text.to_string(), false)];
+            if let Some(above) = above {
+                let upper = above.get(column).map(|s| s.trim()).unwrap_or_default();
+                if !upper.is_empty() {
+                    group = upper.to_string();
+                } else if text.is_empty() {
+                    group.clear();
+                }
+                if text.is_empty() && !upper.is_empty() {
+                    // 纵向合并的表头只有上一格有字
+                    texts.push((upper.to_string(), true));
+                } else if !text.is_empty() && !group.is_empty() {
+                    texts.push((format!("{}{}", group, text), true));
+                }
+            }
+
+            for (field, synonyms) in &self.fields {
+                let best = texts
+                    .iter()
+                    .map(|(t, combined)| (self.score(synonyms, t), t, *combined))
+                    .fold(None::<(f32, &String, bool)>, |best, candidate| match best {
+                        Some(b) if b.0 >= candidate.0 => Some(b),
+                        _ => Some(candidate),
+                    });
+                if let Some((score, text, combined)) = best.filter(|b| b.0 > 0.0) {
+                    candidates.push((
+                        ColumnMatch {
+                            field: field.clone(),
+                            column,
+                            text: text.clone(),
+                            score,
+                            exact: score >= 0.95,
+                        },
+                        combined,
+                    ));
+                }
+            }
+        }
+
+        // 得分高的优先，每列只对应一个字段，每个字段只对应一列
+        candidates.sort_by(|a, b| b.0.score.total_cmp(&a.0.score).then(a.0.column.cmp(&b.0.column)));
+        let mut matches: RowMatches = Vec::new();
+        for candidate in candidates {
+            let taken = matches
+                .iter()
+                .any(|(m, _)| m.field == candidate.0.field || m.column == candidate.0.column);
+            if !taken {
+                matches.push(candidate);
+            }
+        }
+        matches.sort_by_key(|(m, _)| m.column);
+        matches
+    }
+
+    /// 在表格前 `SEARCH_ROWS` 行中查找识别出最多列的一行作为表头
+    ///
+    /// `grid` 为按行排列的单元格文字，合并单元格可以只在左上角有字
+    pub fn detect(&self, grid: &[Vec<String>]) -> Option<HeaderDetection> {
+        let mut best: Option<(usize, RowMatches, f32)> = None;
+        for row in 0..grid.len().min(SEARCH_ROWS) {
+            let matches = self.match_row(grid, row);
+            if matches.len() < MIN_MATCHES {
+                continue;
+            }
+            let total: f32 = matches.iter().map(|(m, _)| m.score).sum();
+            let better = match &best {
+                Some((_, b, b_total)) => matches.len() > b.len() || (matches.len() == b.len() && total > *b_total),
+                None => true,
+            };
+            if better {
+                best = Some((row, matches, total));
+            }
+        }
+
+        let (header_row, matches, total) = best?;
+        let header_rows = if matches.iter().any(|(_, combined)| *combined) { 2 } else { 1 };
+        let unmatched = grid[header_row]
+            .iter()
+            .enumerate()
+            .filter(|(column, text)| !text.trim().is_empty() && !matches.iter().any(|(m, _)| m.column == *column))
+            .map(|(column, text)| (column, text.trim().to_string()))
+            .collect();
+        let confidence = total / matches.len() as f32;
+        Some(HeaderDetection {
+            header_row,
+            header_rows,
+            columns: matches.into_iter().map(|(m, _)| m).collect(),
+            unmatched,
+            confidence,
+        })
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use umya_spreadsheet::{reader, Spreadsheet};
+use super::sheets::select_sheet;
+
+const MAPPING_VERSION: u32 = 1;
+
+// 表格列可以绑定的字段：周报任务字段和月报日报字段
+pub const TABLE_FIELDS: [&str; 15] = [
+    "task", "task_id", "task_name",
+    "plan_start_time", "plan_end_time", "actual_start_time", "actual_end_time",
+    "status", "plan_hours", "actual_hours", "remarks",
+    "date", "should_complete", "completed", "uncompleted",
+];
+// 命名单元格可以绑定的值
+pub const CELL_FIELDS: [&str; 11] = [
+    "title", "start_date", "end_date", "next_week_plan",
+    "year_month", "name", "department", "position", "date", "next_month_plan", "summary",
+];
+
+/// 表格写满后剩余任务的处理方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Overflow {
+    // 在表格最后一行之前插入新行，沿用表格行的样式和公式，表格下方的内容整体下移
+    #[default]
+    Insert,
+    // 继续向下写，可能覆盖表格下方的内容
+    Extend,
+    // 只写到 end_row，多余的任务丢弃
+    Truncate,
+    // 报错，不生成文件
+    Error,
+}
+
+/// 任务表格区域，行号从1开始，列使用 "A"、"B" 这样的列标
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TableMapping {
+    pub header_row: Option<u32>,
+    // 第一行数据，未设置时为表头的下一行
+    pub start_row: Option<u32>,
+    // 表格最后一行（含），未设置时表示不限行数
+    pub end_row: Option<u32>,
+    // 字段 -> 列标，例如 {"task": "D", "status": "G"}
+    pub columns: BTreeMap<String, String>,
+    pub overflow: Overflow,
+}
+
+/// Excel模板的映射说明，保存在模板旁边的 `<模板名>.mapping.json`
+///
+/// 用于代替按固定坐标或表头文字猜测写入位置
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TemplateMapping {
+    pub version: u32,
+    // 工作表名称，未设置时使用第一个工作表
+    pub sheet: Option<String>,
+    // 值 -> 单元格，例如 {"name": "F2", "next_week_plan": "A20"}
+    pub cells: BTreeMap<String, String>,
+    pub table: Option<TableMapping>,
+}
+
+impl Default for TemplateMapping {
+    fn default() -> Self {
+        TemplateMapping {
+            version: MAPPING_VERSION,
+            sheet: None,
+            cells: BTreeMap::new(),
+            table: None,
+        }
+    }
+}
+
+/// 单元格位置 (列, 行)，从1开始
+pub type CellPosition = (u32, u32);
+
+/// 表格区域解析后的行列号（均从1开始）
+pub struct TableLayout {
+    pub start_row: u32,
+    pub end_row: Option<u32>,
+    pub columns: Vec<(String, u32)>,
+    pub overflow: Overflow,
+}
+
+impl TableMapping {
+    pub fn layout(&self) -> Result<TableLayout, String> {
+        let start_row = match (self.start_row, self.header_row) {
+            (Some(row), _) => row,
+            (None, Some(header)) => header + 1,
+            (None, None) => return Err("表格需要设置 start_row 或 header_row".to_string()),
+        };
+        let columns = self
+            .columns
+            .iter()
+            .map(|(field, column)| Ok((field.clone(), parse_column(column)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(TableLayout { start_row, end_row: self.end_row, columns, overflow: self.overflow })
+    }
+}
+
+impl TableLayout {
+    /// 表格能容纳的行数，None 表示不限
+    pub fn capacity(&self) -> Option<usize> {
+        self.end_row.map(|end| (end + 1).saturating_sub(self.start_row) as usize)
+    }
+}
+
+impl TemplateMapping {
+    /// 原先 `generate_monthly_report` 中写死的位置：B2/D2/F2/H2 为表头，第6到19行为表格，A20 为下月计划
+    pub fn monthly_default() -> Self {
+        let cells = [("position", "B2"), ("department", "D2"), ("name", "F2"), ("date", "H2"), ("next_month_plan", "A20")];
+        let columns = [("should_complete", "A"), ("status", "B"), ("remarks", "C")];
+        TemplateMapping {
+            cells: cells.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            table: Some(TableMapping {
+                start_row: Some(6),
+                end_row: Some(19),
+                columns: columns.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// 解析命名单元格，返回 值 -> (列, 行)
+    pub fn cell_positions(&self) -> Result<Vec<(String, CellPosition)>, String> {
+        self.cells
+            .iter()
+            .map(|(name, address)| Ok((name.clone(), parse_cell(address)?)))
+            .collect()
+    }
+
+    /// 按名称、通配符或第一个工作表确定要写入的工作表
+    pub fn sheet_name(&self, book: &Spreadsheet) -> Result<String, String> {
+        select_sheet(&sheet_names(book), self.sheet.as_deref())
+    }
+}
+
+pub fn sheet_names(book: &Spreadsheet) -> Vec<String> {
+    book.get_sheet_collection()
+        .iter()
+        .map(|sheet| sheet.get_name().to_string())
+        .collect()
+}
+
+/// "A" -> 1，"AA" -> 27
+pub fn parse_column(column: &str) -> Result<u32, String> {
+    let column = column.trim().to_ascii_uppercase();
+    if column.is_empty() || column.len() > 3 || !column.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(format!("无效的列标: {}", column));
+    }
+    let index = column.chars().fold(0u32, |acc, c| acc * 26 + (c as u32 - 'A' as u32 + 1));
+    if index > 16384 {
+        return Err(format!("无效的列标: {}", column));
+    }
+    Ok(index)
+}
+
+/// 1 -> "A"，27 -> "AA"
+pub fn column_name(index: u32) -> String {
+    let mut index = index;
+    let mut name = Vec::new();
+    while index > 0 {
+        let rem = (index - 1) % 26;
+        name.push((b'A' + rem as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// "B2" -> (2, 2)，即 (列, 行)，与 umya-spreadsheet 的坐标顺序一致
+pub fn parse_cell(address: &str) -> Result<CellPosition, String> {
+    let address = address.trim().replace('$', "");
+    let split = address.find(|c: char| c.is_ascii_digit()).ok_or_else(|| format!("无效的单元格地址: {}", address))?;
+    let column = parse_column(&address[..split]).map_err(|_| format!("无效的单元格地址: {}", address))?;
+    let row: u32 = address[split..]
+        .parse()
+        .ok()
+        .filter(|row| (1..=1_048_576).contains(row))
+        .ok_or_else(|| format!("无效的单元格地址: {}", address))?;
+    Ok((column, row))
+}
+
+/// "A1:C2" -> ((1, 1), (3, 2))，单个单元格时起止相同
+pub fn parse_range(range: &str) -> Result<(CellPosition, CellPosition), String> {
+    match range.split_once(':') {
+        Some((start, end)) => Ok((parse_cell(start)?, parse_cell(end)?)),
+        None => parse_cell(range).map(|cell| (cell, cell)),
+    }
+}
+
+/// 模板对应的映射文件路径：`周报.xlsx` -> `周报.mapping.json`
+pub fn mapping_path(template_path: &str) -> PathBuf {
+    Path::new(template_path).with_extension("mapping.json")
+}
+
+/// 读取模板旁边的映射文件，没有映射文件时返回 None
+pub fn load_mapping(template_path: &str) -> Result<Option<TemplateMapping>, String> {
+    let path = mapping_path(template_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(&path).map_err(|e| format!("读取映射文件失败: {}", e))?;
+    let mapping: TemplateMapping = serde_json::from_str(&json).map_err(|e| format!("解析映射文件失败: {}", e))?;
+    if mapping.version > MAPPING_VERSION {
+        return Err(format!("映射文件版本 {} 高于当前支持的版本 {}", mapping.version, MAPPING_VERSION));
+    }
+    Ok(Some(mapping))
+}
+
+pub fn save_mapping(template_path: &str, mapping: &TemplateMapping) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(mapping).map_err(|e| format!("序列化映射失败: {}", e))?;
+    fs::write(mapping_path(template_path), json).map_err(|e| format!("保存映射文件失败: {}", e))
+}
+
+/// 映射检查发现的问题
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MappingIssue {
+    pub severity: String, // error：无法按映射导出；warning：可以导出但结果可能不符合预期
+    pub message: String,
+    // 出问题的映射项，例如 "cells.name"、"table.columns.task"
+    pub key: Option<String>,
+}
+
+/// 映射检查结果，存在 error 时 `valid` 为 false
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MappingValidation {
+    pub valid: bool,
+    pub issues: Vec<MappingIssue>,
+    // 表头行中各绑定列的文字，便于在界面上核对
+    pub headers: HashMap<String, String>,
+}
+
+fn issue(severity: &str, message: String, key: Option<String>) -> MappingIssue {
+    MappingIssue { severity: severity.to_string(), message, key }
+}
+
+/// 对照模板文件检查映射：地址格式、字段名、工作表、表格范围以及命名单元格是否落在表格内
+pub fn validate_mapping(template_path: &str, mapping: &TemplateMapping) -> Result<MappingValidation, String> {
+    let book = reader::xlsx::read(template_path).map_err(|e| format!("读取模板失败: {}", e))?;
+    let mut issues = Vec::new();
+    let mut headers = HashMap::new();
+
+    let mut cells = Vec::new();
+    for (name, address) in &mapping.cells {
+        let key = Some(format!("cells.{}", name));
+        if !CELL_FIELDS.contains(&name.as_str()) {
+            issues.push(issue("warning", format!("未知的单元格名称: {}，导出时不会写入", name), key.clone()));
+        }
+        match parse_cell(address) {
+            Ok(position) => cells.push((name.clone(), position)),
+            Err(e) => issues.push(issue("error", e, key)),
+        }
+    }
+
+    let sheet = match mapping.sheet_name(&book) {
+        Ok(name) => book.get_sheet_by_name(&name),
+        Err(e) => {
+            issues.push(issue("error", e, Some("sheet".to_string())));
+            None
+        }
+    };
+
+    if let Some(table) = &mapping.table {
+        let mut used = HashSet::new();
+        for (field, column) in &table.columns {
+            let key = Some(format!("table.columns.{}", field));
+            if !TABLE_FIELDS.contains(&field.as_str()) {
+                issues.push(issue("warning", format!("未知的字段: {}，导出时不会写入", field), key.clone()));
+            }
+            match parse_column(column) {
+                Ok(index) if !used.insert(index) => {
+                    issues.push(issue("error", format!("列 {} 绑定了多个字段", column), key));
+                }
+                Ok(_) => {}
+                Err(e) => issues.push(issue("error", e, key)),
+            }
+        }
+
+        match table.layout() {
+            Ok(layout) => {
+                if table.header_row.is_some_and(|header| header >= layout.start_row) {
+                    issues.push(issue("error", "start_row 必须位于表头之后".to_string(), Some("table.start_row".to_string())));
+                }
+                if layout.end_row.is_some_and(|end| end < layout.start_row) {
+                    issues.push(issue("error", "end_row 不能小于 start_row".to_string(), Some("table.end_row".to_string())));
+                }
+                if layout.end_row.is_none() && table.overflow != Overflow::Extend {
+                    issues.push(issue(
+                        "warning",
+                        "未设置 end_row，只有在表格下方找到收尾内容（如\"下周计划\"、\"合计\"）时 overflow 才会生效".to_string(),
+                        Some("table.overflow".to_string()),
+                    ));
+                }
+                if layout.columns.is_empty() {
+                    issues.push(issue("error", "表格没有绑定任何列".to_string(), Some("table.columns".to_string())));
+                }
+
+                // 命名单元格落在数据区内会被任务覆盖
+                for (name, (column, row)) in &cells {
+                    let in_rows = *row >= layout.start_row && !layout.end_row.is_some_and(|end| *row > end);
+                    if in_rows && layout.columns.iter().any(|(_, c)| c == column) {
+                        issues.push(issue(
+                            "warning",
+                            format!("单元格 {} 位于表格数据区内，可能被任务覆盖", mapping.cells[name]),
+                            Some(format!("cells.{}", name)),
+                        ));
+                    }
+                }
+
+                if let (Some(sheet), Some(header_row)) = (sheet, table.header_row) {
+                    for (field, column) in &layout.columns {
+                        let text = sheet.get_value((*column, header_row)).trim().to_string();
+                        if text.is_empty() {
+                            issues.push(issue(
+                                "warning",
+                                format!("表头第 {} 行 {} 列为空", header_row, table.columns[field]),
+                                Some(format!("table.columns.{}", field)),
+                            ));
+                        }
+                        headers.insert(field.clone(), text);
+                    }
+                }
+            }
+            Err(e) => issues.push(issue("error", e, Some("table".to_string()))),
+        }
+    } else if mapping.cells.is_empty() {
+        issues.push(issue("warning", "映射中没有单元格也没有表格，导出时不会写入任何内容".to_string(), None));
+    }
+
+    Ok(MappingValidation {
+        valid: !issues.iter().any(|i| i.severity == "error"),
+        issues,
+        headers,
+    })
+}
@@ -2,60 +2,135 @@
 mod parser;
 mod generator;
 mod template;
-mod utils;
+pub mod mapping;
+pub mod header;
+pub mod sheets;
+mod rows;
+mod values;
+mod workbook;
+pub mod output;
+pub mod utils;
+#[cfg(test)]
+mod tests;
 
 // Re-exports with #[tauri::command] macro preservation
+// 支持 xlsx、xls、xlsb、ods 和 CSV/TSV，按文件内容判断格式
+// sheet 为工作表名称或通配符，未指定时使用第一个能识别出表头的工作表
 #[tauri::command]
-pub fn parse_excel_template(excel_data: Vec<u8>) -> Result<crate::database::types::ReportTemplate, String> {
-    parser::parse_excel_template(excel_data)
+pub fn parse_excel_template(excel_data: Vec<u8>, sheet: Option<String>) -> Result<crate::database::types::ReportTemplate, String> {
+    parser::parse_excel_template(excel_data, sheet.as_deref())
 }
 
+// 列出Excel中的工作表及各自识别到的表头
 #[tauri::command]
-pub fn import_excel_tasks(file_data: Vec<u8>) -> Result<Vec<crate::database::types::Task>, String> {
-    // 创建临时文件
-    let temp_dir = tempfile::Builder::new().prefix("excel_import").tempdir()
-        .map_err(|e| format!("无法创建临时目录: {}", e))?;
-    let temp_path = temp_dir.path().join("import.xlsx");
-    let temp_path_str = temp_path.to_str().ok_or("路径转换失败")?.to_string();
-    
-    // 写入Excel数据到临时文件
-    std::fs::write(&temp_path, &file_data)
-        .map_err(|e| format!("写入临时文件失败: {}", e))?;
-    
-    // 调用原始函数处理文件
-    parser::import_excel_tasks(temp_path_str)
+pub fn list_excel_sheets(excel_data: Vec<u8>) -> Result<Vec<sheets::SheetInfo>, String> {
+    parser::list_sheets(excel_data)
 }
 
+// 识别Excel模板的表头，返回各列对应的字段和可信度
 #[tauri::command]
-pub fn generate_excel_report(
-    app_handle: tauri::AppHandle,
+pub fn detect_excel_headers(excel_data: Vec<u8>, sheet: Option<String>) -> Result<header::HeaderDetection, String> {
+    parser::detect_headers(excel_data, sheet.as_deref())
+}
+
+// 当前生效的表头同义词（内置加自定义）
+#[tauri::command]
+pub fn get_header_synonyms() -> std::collections::BTreeMap<String, Vec<String>> {
+    header::HeaderDictionary::from_config().entries()
+}
+
+// 保存自定义的表头同义词，会追加到内置同义词之后
+#[tauri::command]
+pub fn save_header_synonyms(synonyms: std::collections::BTreeMap<String, Vec<String>>) -> Result<(), String> {
+    header::save_custom_synonyms(&synonyms)
+}
+
+// 导入前预览：按表头识别或 mapping 中指定的列（{"columns": {"task": "B", "status": "E"}}）读取任务
+// 返回各字段对应的列、没有对应字段的列和前几行任务，确认后用同样的参数调用 import_excel_tasks
+#[tauri::command]
+pub fn preview_excel_import(
+    file_data: Vec<u8>,
+    sheet: Option<String>,
+    mapping: Option<mapping::TableMapping>,
+) -> Result<crate::database::types::ImportPreview, String> {
+    parser::preview_import(file_data, sheet.as_deref(), mapping.as_ref())
+}
+
+#[tauri::command]
+pub fn import_excel_tasks(
+    file_data: Vec<u8>,
+    sheet: Option<String>,
+    mapping: Option<mapping::TableMapping>,
+) -> Result<Vec<crate::database::types::Task>, String> {
+    parser::import_excel_tasks(file_data, sheet.as_deref(), mapping.as_ref())
+}
+
+// sink 为输出方式：{"type": "path", "path": "..."} 保存到文件，{"type": "bytes"}（默认）返回文件内容，
+// {"type": "notify", "channel_ids": [...]} 作为附件发送到通知渠道
+#[tauri::command]
+pub async fn generate_excel_report(
     start_date: String,
     end_date: String,
     tasks: Vec<crate::database::types::Task>,
     next_week_plan: String,
-) -> Result<(), String> {
-    generator::generate_excel_report(app_handle, start_date, end_date, tasks, next_week_plan)
+    sink: Option<output::ExportSink>,
+) -> Result<output::ExportOutput, String> {
+    let sink = sink.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        generator::export_weekly_excel(&start_date, &end_date, tasks, &next_week_plan, &sink)
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
+// 生成周报Excel的内容或直接交给输出方式，不依赖界面，供定时任务使用
+pub use generator::{export_weekly_excel, weekly_file_name};
+
+// 返回导出时发现的问题，例如状态不在模板的下拉列表中
 #[tauri::command]
 pub fn export_with_template(
     template_path: String,
     output_path: String,
     tasks: Vec<crate::database::types::Task>,
     next_week_plan: String,
-) -> Result<(), String> {
-    generator::export_with_template(template_path, output_path, tasks, next_week_plan)
+    options: Option<sheets::SheetExportOptions>,
+) -> Result<Vec<String>, String> {
+    generator::export_with_template(template_path, output_path, tasks, next_week_plan, options.unwrap_or_default())
 }
 
 #[tauri::command]
 pub fn generate_monthly_report(
-    app_handle: tauri::AppHandle,
     template_path: String,
     output_path: String,
     year_month: String,
     user_info: crate::database::types::UserInfo,
 ) -> Result<(), String> {
-    generator::generate_monthly_report(app_handle, template_path, output_path, year_month, user_info)
+    generator::generate_monthly_report(template_path, output_path, year_month, user_info)
+}
+
+// 读取模板旁边的映射文件，没有时返回 null
+#[tauri::command]
+pub fn load_template_mapping(template_path: String) -> Result<Option<mapping::TemplateMapping>, String> {
+    mapping::load_mapping(&template_path)
+}
+
+#[tauri::command]
+pub fn save_template_mapping(template_path: String, mapping: mapping::TemplateMapping) -> Result<(), String> {
+    mapping::save_mapping(&template_path, &mapping)
+}
+
+// 对照模板检查映射，未传入 mapping 时检查已保存的映射文件
+#[tauri::command]
+pub fn validate_template_mapping(
+    template_path: String,
+    mapping: Option<mapping::TemplateMapping>,
+) -> Result<mapping::MappingValidation, String> {
+    let mapping = match mapping {
+        Some(mapping) => mapping,
+        None => mapping::load_mapping(&template_path)?
+            .ok_or_else(|| format!("没有找到映射文件: {}", mapping::mapping_path(&template_path).display()))?,
+    };
+    mapping::validate_mapping(&template_path, &mapping)
 }
 
 #[tauri::command]
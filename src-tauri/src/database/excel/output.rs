@@ -0,0 +1,70 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::notifier::{send_report_to_channels, Attachment, ChannelSendResult, OutgoingReport};
+
+pub const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// 生成的文件交给谁处理，界面、定时任务和测试共用同一套生成逻辑
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportSink {
+    // 保存到指定路径，目录不存在时自动创建
+    Path { path: String },
+    // 把文件内容返回给界面，由界面负责保存
+    #[default]
+    Bytes,
+    // 作为附件发送到通知渠道（目前只有邮件渠道会带上附件）
+    Notify {
+        channel_ids: Vec<String>,
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+/// 各输出方式的结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportOutput {
+    Saved { path: String },
+    Bytes { file_name: String, data: Vec<u8> },
+    Sent { results: Vec<ChannelSendResult> },
+}
+
+/// 一份生成好的文件
+pub struct ExportFile {
+    pub kind: String, // weekly / monthly
+    pub title: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// 把生成好的文件交给指定的输出方式
+pub fn deliver(file: ExportFile, sink: &ExportSink) -> Result<ExportOutput, String> {
+    match sink {
+        ExportSink::Path { path } => {
+            if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败: {}", e))?;
+            }
+            std::fs::write(path, &file.data).map_err(|e| format!("保存文件失败: {}", e))?;
+            Ok(ExportOutput::Saved { path: path.clone() })
+        }
+        ExportSink::Bytes => Ok(ExportOutput::Bytes { file_name: file.file_name, data: file.data }),
+        ExportSink::Notify { channel_ids, message } => {
+            if channel_ids.is_empty() {
+                return Err("未选择发送渠道".to_string());
+            }
+            let report = OutgoingReport {
+                kind: file.kind,
+                markdown: message.clone().unwrap_or_else(|| format!("{}，详见附件 {}", file.title, file.file_name)),
+                title: file.title,
+                attachments: vec![Attachment {
+                    file_name: file.file_name,
+                    content_type: file.content_type,
+                    data: file.data,
+                }],
+            };
+            Ok(ExportOutput::Sent { results: send_report_to_channels(channel_ids, &report)? })
+        }
+    }
+}
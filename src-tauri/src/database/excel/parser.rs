@@ -1,95 +1,203 @@
-use calamine::{Reader, Xlsx, open_workbook, DataType};
-use std::fs;
+use calamine::{DataType, Range};
 use std::collections::HashMap;
-use tempfile::Builder;
-use crate::database::types::{Task, ReportTemplate};
+use crate::database::types::{Task, ReportTemplate, ImportColumn, ImportPreview, ImportWarning};
+use super::header::{HeaderDetection, HeaderDictionary, SEARCH_COLUMNS, SEARCH_ROWS};
+use super::mapping::{column_name, parse_column, TableMapping, TABLE_FIELDS};
+use super::sheets::{find_sheet, SheetInfo};
+use super::workbook::Workbook;
+use super::values::{format_number, normalize_status, parse_date, parse_hours, serial_to_date, DATE_FIELDS, HOUR_FIELDS};
 
-pub fn parse_excel_template(excel_data: Vec<u8>) -> Result<ReportTemplate, String> {
-    // 创建临时文件
-    let temp_dir = Builder::new().prefix("excel_template").tempdir()
-        .map_err(|e| format!("无法创建临时目录: {}", e))?;
-    let temp_path = temp_dir.path().join("template.xlsx");
-    
-    // 写入Excel数据到临时文件
-    fs::write(&temp_path, &excel_data)
-        .map_err(|e| format!("写入临时文件失败: {}", e))?;
-    
-    // 使用calamine打开Excel文件
-    let mut workbook: Xlsx<_> = open_workbook(&temp_path)
-        .map_err(|e| format!("打开Excel文件失败: {}", e))?;
-    
-    let sheet_name = workbook.sheet_names().get(0)
-        .ok_or("Excel文件没有工作表".to_string())?
-        .clone();
-    
-    // 正确处理Option<Result<Range, XlsxError>>
-    let range = match workbook.worksheet_range(&sheet_name) {
-        Some(Ok(range)) => range,
-        Some(Err(e)) => return Err(format!("读取工作表出错: {}", e)),
-        None => return Err("没有找到工作表".to_string()),
+// 导入预览中显示的任务行数
+const PREVIEW_ROWS: usize = 10;
+
+// 选择工作表：指定了名称或通配符时按其选择，否则使用第一个能识别出表头的工作表（跳过封面等），都识别不出时使用第一个
+fn read_sheet(workbook: &mut Workbook, sheet: Option<&str>) -> Result<(String, Range<DataType>), String> {
+    let names = workbook.sheet_names();
+    if names.is_empty() {
+        return Err("Excel文件没有工作表".to_string());
+    }
+    if let Some(pattern) = sheet.filter(|s| !s.trim().is_empty()) {
+        let name = names[find_sheet(&names, pattern)?].clone();
+        let range = workbook.sheet_range(&name)?;
+        return Ok((name, range));
+    }
+
+    let dictionary = HeaderDictionary::from_config();
+    let mut first = None;
+    for name in &names {
+        let range = workbook.sheet_range(name)?;
+        if dictionary.detect(&header_grid(&range)).is_some() {
+            return Ok((name.clone(), range));
+        }
+        first.get_or_insert((name.clone(), range));
+    }
+    first.ok_or_else(|| "Excel文件没有工作表".to_string())
+}
+
+// 表格左上角区域的单元格文字，用于识别表头
+fn header_grid(range: &Range<DataType>) -> Vec<Vec<String>> {
+    (0..range.height().min(SEARCH_ROWS))
+        .map(|row| {
+            (0..range.width().min(SEARCH_COLUMNS))
+                .map(|col| range.get((row, col)).and_then(cell_text).unwrap_or_default())
+                .collect()
+        })
+        .collect()
+}
+
+// 单元格的文字：日期单元格转为 yyyy-mm-dd，时长转为小时数，布尔值转为 是/否
+fn cell_text(cell: &DataType) -> Option<String> {
+    match cell {
+        DataType::String(s) => Some(s.clone()),
+        DataType::Float(f) => Some(f.to_string()),
+        DataType::Int(i) => Some(i.to_string()),
+        DataType::Bool(b) => Some(if *b { "是" } else { "否" }.to_string()),
+        DataType::DateTime(serial) => serial_to_date(*serial).map(|date| date.format("%Y-%m-%d").to_string()),
+        // [h]:mm 格式的时长以天为单位
+        DataType::Duration(days) => Some(format_number(days * 24.0)),
+        DataType::DateTimeIso(s) => Some(parse_date(s).map_or_else(|| s.clone(), |date| date.format("%Y-%m-%d").to_string())),
+        _ => None,
+    }
+}
+
+// 按字段类型读取单元格：日期统一为 yyyy-mm-dd，工时统一为小时数，状态统一为标准写法
+// 无法识别时保留原文，并记录一条警告（`row` 为Excel中的行号）
+fn field_value(field: &str, cell: &DataType, row: usize, warnings: &mut Vec<ImportWarning>) -> Option<String> {
+    let text = cell_text(cell).filter(|t| !t.trim().is_empty())?;
+    let (value, problem) = if DATE_FIELDS.contains(&field) {
+        let date = match cell {
+            DataType::DateTime(serial) | DataType::Float(serial) => serial_to_date(*serial),
+            DataType::Int(serial) => serial_to_date(*serial as f64),
+            // CSV中的日期可能是以文字保存的序列号
+            _ => parse_date(&text).or_else(|| text.trim().parse().ok().and_then(serial_to_date)),
+        };
+        match date {
+            Some(date) => (date.format("%Y-%m-%d").to_string(), None),
+            None => (text.clone(), Some("无法识别的日期")),
+        }
+    } else if HOUR_FIELDS.contains(&field) {
+        let hours = match cell {
+            DataType::Float(f) => Some(*f),
+            DataType::Int(i) => Some(*i as f64),
+            DataType::Duration(days) => Some(days * 24.0),
+            _ => parse_hours(&text),
+        };
+        match hours {
+            Some(hours) => (format_number(hours), None),
+            None => (text.clone(), Some("无法识别的工时")),
+        }
+    } else if field == "status" {
+        match cell {
+            // 勾选框形式的完成列
+            DataType::Bool(done) => ((if *done { "已完成" } else { "进行中" }).to_string(), None),
+            _ => match normalize_status(&text) {
+                Some(status) => (status.to_string(), None),
+                None => (text.trim().to_string(), Some("未知的任务状态")),
+            },
+        }
+    } else {
+        (text.clone(), None)
+    };
+
+    if let Some(message) = problem {
+        warnings.push(ImportWarning {
+            row,
+            field: field.to_string(),
+            value: text,
+            message: message.to_string(),
+        });
+    }
+    Some(value)
+}
+
+// 读取一行任务，`columns` 为字段对应的列（从0开始）；任务内容为空或是下周计划的标题行时返回 None
+fn read_task(
+    range: &Range<DataType>,
+    row_idx: usize,
+    columns: &HashMap<String, usize>,
+    warnings: &mut Vec<ImportWarning>,
+) -> Option<Task> {
+    let row = range.start().map_or(0, |(start, _)| start as usize) + row_idx + 1;
+    let mut value = |field: &str| {
+        columns
+            .get(field)
+            .and_then(|&col| range.get((row_idx, col)))
+            .and_then(|cell| field_value(field, cell, row, warnings))
     };
+
+    let task = value("task")?;
+    // 跳过下周计划行
+    if task.trim().is_empty() || (task.contains("下周") && task.contains("计划")) {
+        return None;
+    }
+    Some(Task {
+        task,
+        status: value("status").unwrap_or_else(|| "进行中".to_string()),
+        remarks: value("remarks").unwrap_or_default(),
+        task_id: value("task_id"),
+        task_name: value("task_name"),
+        plan_start_time: value("plan_start_time"),
+        plan_end_time: value("plan_end_time"),
+        actual_start_time: value("actual_start_time"),
+        actual_end_time: value("actual_end_time"),
+        plan_hours: value("plan_hours"),
+        actual_hours: value("actual_hours"),
+    })
+}
+
+/// 识别上传的Excel模板的表头，返回各列对应的字段和可信度
+pub fn detect_headers(excel_data: Vec<u8>, sheet: Option<&str>) -> Result<HeaderDetection, String> {
+    let mut workbook = Workbook::open(excel_data)?;
+    let (_, range) = read_sheet(&mut workbook, sheet)?;
+    HeaderDictionary::from_config()
+        .detect(&header_grid(&range))
+        .ok_or_else(|| "未能识别表头，请检查模板格式或补充表头同义词".to_string())
+}
+
+/// 列出所有工作表及识别到的表头，供界面选择要导入的工作表
+pub fn list_sheets(excel_data: Vec<u8>) -> Result<Vec<SheetInfo>, String> {
+    let mut workbook = Workbook::open(excel_data)?;
+    let dictionary = HeaderDictionary::from_config();
+    let names = workbook.sheet_names();
+    let mut sheets = Vec::new();
+    for (index, name) in names.into_iter().enumerate() {
+        let range = workbook.sheet_range(&name)?;
+        sheets.push(SheetInfo {
+            index,
+            rows: range.height(),
+            columns: range.width(),
+            header: dictionary.detect(&header_grid(&range)),
+            name,
+        });
+    }
+    Ok(sheets)
+}
+
+pub fn parse_excel_template(excel_data: Vec<u8>, sheet: Option<&str>) -> Result<ReportTemplate, String> {
+    let mut workbook = Workbook::open(excel_data)?;
+    let (_, range) = read_sheet(&mut workbook, sheet)?;
     
     // 解析Excel内容
     let mut tasks = Vec::new();
     let mut next_week_plan = String::new();
 
-    // 尝试识别表格结构的列索引
+    // 识别表格结构的列索引
     let mut col_indices = HashMap::new();
-    
-    // 要识别的列名称
-    let column_names = [
-        ("task_id", vec!["任务编号", "编号", "ID"]),
-        ("task_name", vec!["任务名称", "名称"]),
-        ("task", vec!["任务内容", "内容", "量化指标", "工作内容"]),
-        ("plan_start", vec!["计划开始时间", "计划开始"]),
-        ("plan_end", vec!["计划结束时间", "计划结束"]),
-        ("actual_start", vec!["实际开始时间", "实际开始"]),
-        ("actual_end", vec!["实际结束时间", "实际结束"]),
-        ("status", vec!["任务状态", "状态", "完成状态"]),
-        ("plan_hours", vec!["计划工时", "计划工时(小时)"]),
-        ("actual_hours", vec!["实际工时", "实际工时(小时)"]),
-        ("remarks", vec!["备注", "说明"])
-    ];
-
-    // 搜索表头找列索引
-    'header_search: for row_idx in 0..std::cmp::min(20, range.height()) {
-        let mut found_count = 0;
-        
-        for col_idx in 0..std::cmp::min(20, range.width()) {
-            if let Some(cell) = range.get((row_idx, col_idx)) {
-                if let Some(text) = cell.get_string() {
-                    let text = text.trim();
-                    
-                    for (col_key, synonyms) in &column_names {
-                        for &synonym in synonyms {
-                            if text.contains(synonym) {
-                                col_indices.insert(col_key.to_string(), col_idx);
-                                found_count += 1;
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // 如果找到多个列头，认为这是表头行，记住行号并停止搜索
-        if found_count >= 3 {
-            col_indices.insert("header_row".to_string(), row_idx);
-            break 'header_search;
+    if let Some(detection) = HeaderDictionary::from_config().detect(&header_grid(&range)) {
+        for column in &detection.columns {
+            col_indices.insert(column.field.clone(), column.column);
         }
+        col_indices.insert("header_row".to_string(), detection.header_row);
     }
     
-    // 必须至少有任务内容列
-    let task_col = match col_indices.get("task") {
-        Some(&idx) => idx,
-        None => {
-            // 如果没找到，尝试使用第一列
-            if range.width() > 0 { 0 } else { 
-                return Err("表格结构无法识别，未找到任务内容列".to_string()) 
-            }
+    // 必须至少有任务内容列，没找到时尝试使用第一列
+    let mut columns = col_indices.clone();
+    if !columns.contains_key("task") {
+        if range.width() == 0 {
+            return Err("表格结构无法识别，未找到任务内容列".to_string());
         }
-    };
+        columns.insert("task".to_string(), 0);
+    }
     
     // 计算起始行（表头行+1）
     let start_row = match col_indices.get("header_row") {
@@ -98,133 +206,10 @@ pub fn parse_excel_template(excel_data: Vec<u8>) -> Result<ReportTemplate, Strin
     };
     
     // 从表格中提取任务数据
+    let mut warnings = Vec::new();
     for row_idx in start_row..range.height() {
-        // 获取任务内容
-        let task_content = range.get((row_idx, task_col))
-            .and_then(|cell| {
-                match cell {
-                    DataType::String(s) => Some(s.clone()),
-                    DataType::Float(f) => Some(f.to_string()),
-                    DataType::Int(i) => Some(i.to_string()),
-                    _ => None,
-                }
-            });
-        
-        // 如果有任务内容并且不是空字符串
-        if let Some(task) = task_content {
-            if !task.trim().is_empty() && 
-               !(task.contains("下周") && task.contains("计划")) { // 跳过下周计划行
-                // 构建任务对象
-                let mut task_obj = Task {
-                    task,
-                    status: "进行中".to_string(),
-                    remarks: String::new(),
-                    task_id: None,
-                    task_name: None,
-                    plan_start_time: None,
-                    plan_end_time: None,
-                    actual_start_time: None,
-                    actual_end_time: None,
-                    plan_hours: None,
-                    actual_hours: None,
-                };
-                
-                // 填充其他字段
-                if let Some(&col) = col_indices.get("status") {
-                    task_obj.status = range.get((row_idx, col))
-                        .and_then(|cell| cell.get_string().map(|s| s.to_string()))
-                        .unwrap_or_else(|| "进行中".to_string());
-                }
-                
-                if let Some(&col) = col_indices.get("remarks") {
-                    task_obj.remarks = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        })
-                        .unwrap_or_default();
-                }
-                
-                // 填充其他可选字段
-                if let Some(&col) = col_indices.get("task_id") {
-                    task_obj.task_id = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("task_name") {
-                    task_obj.task_name = range.get((row_idx, col))
-                        .and_then(|cell| cell.get_string().map(|s| s.to_string()));
-                }
-                
-                if let Some(&col) = col_indices.get("plan_start") {
-                    task_obj.plan_start_time = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("plan_end") {
-                    task_obj.plan_end_time = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("actual_start") {
-                    task_obj.actual_start_time = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("actual_end") {
-                    task_obj.actual_end_time = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("plan_hours") {
-                    task_obj.plan_hours = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                if let Some(&col) = col_indices.get("actual_hours") {
-                    task_obj.actual_hours = range.get((row_idx, col))
-                        .and_then(|cell| match cell {
-                            DataType::String(s) => Some(s.clone()),
-                            DataType::Float(f) => Some(f.to_string()),
-                            DataType::Int(i) => Some(i.to_string()),
-                            _ => None,
-                        });
-                }
-                
-                tasks.push(task_obj);
-            }
+        if let Some(task) = read_task(&range, row_idx, &columns, &mut warnings) {
+            tasks.push(task);
         }
     }
     
@@ -265,61 +250,155 @@ pub fn parse_excel_template(excel_data: Vec<u8>) -> Result<ReportTemplate, Strin
         tasks,
         next_week_plan,
         column_indices: col_indices,
+        warnings,
     })
 }
 
-pub fn import_excel_tasks(file_path: String) -> Result<Vec<Task>, String> {
-    let mut workbook: Xlsx<_> = open_workbook(&file_path)
-        .map_err(|e| format!("无法打开Excel文件: {}", e))?;
-    
-    let sheet_name = workbook.sheet_names().get(0)
-        .ok_or_else(|| "Excel文件中没有工作表".to_string())?
-        .clone();
-    
-    // 正确处理Option<Result<Range, XlsxError>>
-    let range = match workbook.worksheet_range(&sheet_name) {
-        Some(Ok(range)) => range,
-        Some(Err(e)) => return Err(format!("读取工作表出错: {}", e)),
-        None => return Err("没有找到工作表".to_string()),
+// 导入时各字段对应的列和数据所在的行，行列号相对于工作表区域、从0开始
+struct ImportLayout {
+    // (字段, 列, 来源)
+    columns: Vec<(String, usize, &'static str)>,
+    header_row: Option<usize>,
+    start_row: usize,
+    end_row: Option<usize>,
+}
+
+impl ImportLayout {
+    fn indices(&self) -> HashMap<String, usize> {
+        self.columns.iter().map(|(field, col, _)| (field.clone(), *col)).collect()
+    }
+}
+
+// 确定导入的列：先按表头识别，再用界面指定的映射覆盖（列标留空表示不导入该字段）
+// 既没有识别到表头也没有指定映射时，沿用原来的固定格式：第1列任务内容，第2列状态，第3列备注
+fn import_layout(range: &Range<DataType>, mapping: Option<&TableMapping>) -> Result<ImportLayout, String> {
+    let (row_offset, col_offset) = range.start().map_or((0, 0), |(row, col)| (row as usize, col as usize));
+    let to_row = |row: u32| {
+        (row as usize)
+            .checked_sub(row_offset + 1)
+            .ok_or_else(|| format!("第{}行不在表格数据区域内", row))
     };
-    
+
+    let detection = HeaderDictionary::from_config().detect(&header_grid(range));
+    let mut layout = ImportLayout {
+        columns: Vec::new(),
+        header_row: detection.as_ref().map(|d| d.header_row),
+        start_row: detection.as_ref().map_or(1, |d| d.header_row + 1),
+        end_row: None,
+    };
+    if let Some(detection) = &detection {
+        for column in &detection.columns {
+            layout.columns.push((column.field.clone(), column.column, "detected"));
+        }
+    }
+
+    if let Some(mapping) = mapping {
+        for (field, column) in &mapping.columns {
+            if !TABLE_FIELDS.contains(&field.as_str()) {
+                return Err(format!("未知的字段: {}", field));
+            }
+            layout.columns.retain(|(f, _, _)| f != field);
+            if column.trim().is_empty() {
+                continue;
+            }
+            let col = (parse_column(column)? as usize)
+                .checked_sub(col_offset + 1)
+                .ok_or_else(|| format!("{}列不在表格数据区域内", column))?;
+            layout.columns.retain(|(_, c, _)| *c != col);
+            layout.columns.push((field.clone(), col, "mapped"));
+        }
+        if let Some(row) = mapping.header_row {
+            layout.header_row = Some(to_row(row)?);
+            layout.start_row = to_row(row)? + 1;
+        }
+        if let Some(row) = mapping.start_row {
+            layout.start_row = to_row(row)?;
+        }
+        layout.end_row = mapping.end_row.map(to_row).transpose()?;
+    }
+
+    if detection.is_none() && layout.columns.is_empty() {
+        layout.columns = vec![
+            ("task".to_string(), 0, "default"),
+            ("status".to_string(), 1, "default"),
+            ("remarks".to_string(), 2, "default"),
+        ];
+    }
+    if !layout.columns.iter().any(|(field, _, _)| field == "task") {
+        return Err("未找到任务内容列，请指定任务内容所在的列".to_string());
+    }
+    layout.columns.sort_by_key(|(_, col, _)| *col);
+    Ok(layout)
+}
+
+// 按导入的列读取所有任务，返回任务和无法识别的单元格
+fn read_tasks(range: &Range<DataType>, layout: &ImportLayout) -> (Vec<Task>, Vec<ImportWarning>) {
+    let columns = layout.indices();
+    let end = layout.end_row.map_or(range.height(), |row| (row + 1).min(range.height()));
     let mut tasks = Vec::new();
-    
-    // 从第2行开始读取数据（跳过标题行）
-    for row_idx in 1..range.height() {
-        if row_idx < range.height() {
-            let task_content = match range.get((row_idx, 0)) {
-                Some(DataType::String(s)) => s.clone(),
-                Some(DataType::Float(f)) => f.to_string(),
-                Some(DataType::Int(i)) => i.to_string(),
-                Some(_) | None => continue,
-            };
-            
-            let status = match range.get((row_idx, 1)) {
-                Some(DataType::String(s)) => s.clone(),
-                Some(_) | None => "进行中".to_string(),
-            };
-            
-            let remarks = match range.get((row_idx, 2)) {
-                Some(DataType::String(s)) => s.clone(),
-                Some(_) | None => "".to_string(),
-            };
-            
-            tasks.push(Task {
-                task: task_content,
-                status,
-                remarks,
-                task_id: None,
-                task_name: None,
-                plan_start_time: None,
-                plan_end_time: None,
-                actual_start_time: None,
-                actual_end_time: None,
-                plan_hours: None,
-                actual_hours: None,
-            });
+    let mut warnings = Vec::new();
+    for row_idx in layout.start_row..end {
+        if let Some(task) = read_task(range, row_idx, &columns, &mut warnings) {
+            tasks.push(task);
         }
     }
-    
+    (tasks, warnings)
+}
+
+/// 导入前的预览：各字段对应的列、没有对应字段的列、前几行任务和无法识别的单元格
+///
+/// 界面确认或调整映射后，用同样的 `mapping` 调用 `import_excel_tasks`
+pub fn preview_import(data: Vec<u8>, sheet: Option<&str>, mapping: Option<&TableMapping>) -> Result<ImportPreview, String> {
+    let mut workbook = Workbook::open(data)?;
+    let (sheet, range) = read_sheet(&mut workbook, sheet)?;
+    let layout = import_layout(&range, mapping)?;
+    let (row_offset, col_offset) = range.start().map_or((0, 0), |(row, col)| (row as usize, col as usize));
+    let letter = |col: usize| column_name((col + col_offset + 1) as u32);
+    let header_text = |col: usize| {
+        layout
+            .header_row
+            .and_then(|row| range.get((row, col)))
+            .and_then(cell_text)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let columns = layout
+        .columns
+        .iter()
+        .map(|(field, col, source)| ImportColumn {
+            field: field.clone(),
+            column: letter(*col),
+            header: header_text(*col),
+            source: source.to_string(),
+        })
+        .collect();
+    let unmapped = (0..range.width())
+        .filter(|col| !layout.columns.iter().any(|(_, c, _)| c == col))
+        .map(|col| (letter(col), header_text(col)))
+        .filter(|(_, header)| !header.is_empty())
+        .collect();
+
+    let (tasks, warnings) = read_tasks(&range, &layout);
+    Ok(ImportPreview {
+        sheet,
+        header_row: layout.header_row.map(|row| (row + row_offset + 1) as u32),
+        start_row: (layout.start_row + row_offset + 1) as u32,
+        columns,
+        unmapped,
+        total_rows: tasks.len(),
+        sample_rows: tasks.into_iter().take(PREVIEW_ROWS).collect(),
+        warnings,
+    })
+}
+
+pub fn import_excel_tasks(data: Vec<u8>, sheet: Option<&str>, mapping: Option<&TableMapping>) -> Result<Vec<Task>, String> {
+    let mut workbook = Workbook::open(data)?;
+    let (_, range) = read_sheet(&mut workbook, sheet)?;
+    let layout = import_layout(&range, mapping)?;
+    let (tasks, warnings) = read_tasks(&range, &layout);
+    for warning in &warnings {
+        eprintln!("第{}行{}: {}（{}）", warning.row, warning.field, warning.message, warning.value);
+    }
     Ok(tasks)
-} 
\ No newline at end of file
+}
@@ -0,0 +1,156 @@
+use regex::Regex;
+use umya_spreadsheet::Worksheet;
+use super::mapping::{column_name, parse_range};
+
+// 表格下方常见的收尾内容，遇到这些文字的行视为表格已结束
+const FOOTER_KEYWORDS: [&str; 8] = ["下周", "下月", "合计", "总计", "小计", "总结", "审核", "签字"];
+// 向下查找表格结尾的最大行数
+const MAX_TABLE_ROWS: u32 = 500;
+
+/// 把公式中的相对行号整体移动 `delta` 行，与在Excel中向下填充公式的效果相同
+///
+/// 带 `$` 的绝对行号和字符串中的内容保持不变
+pub fn shift_formula_rows(formula: &str, delta: i64) -> String {
+    if delta == 0 {
+        return formula.to_string();
+    }
+    let reference = Regex::new(r"(\$?[A-Za-z]{1,3})(\$?)(\d+)").unwrap();
+    formula
+        .split('"')
+        .enumerate()
+        .map(|(i, part)| {
+            // 引号之间是字符串常量
+            if i % 2 == 1 {
+                return part.to_string();
+            }
+            let mut result = String::new();
+            let mut last = 0;
+            for caps in reference.captures_iter(part) {
+                let whole = caps.get(0).unwrap();
+                let before = part[..whole.start()].chars().last();
+                let after = part[whole.end()..].chars().next();
+                // 排除函数名（如 LOG10(）和更长的名称中的一部分
+                let is_reference = !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                    && !after.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '(');
+                if !is_reference || !caps[2].is_empty() {
+                    continue;
+                }
+                let row: i64 = caps[3].parse().unwrap_or(0);
+                if row + delta < 1 {
+                    continue;
+                }
+                result.push_str(&part[last..whole.start()]);
+                result.push_str(&format!("{}{}", &caps[1], row + delta));
+                last = whole.end();
+            }
+            result.push_str(&part[last..]);
+            result
+        })
+        .collect::<Vec<_>>()
+        .join("\"")
+}
+
+/// 从 `start_row` 向下查找表格最后一行：遇到收尾文字或横跨整个表格宽度的合并单元格的上一行
+///
+/// 找不到时返回 None，表示表格下方没有需要保护的内容；收尾内容紧跟在 `start_row` 上时返回
+/// `start_row - 1`，即没有数据行的空表格，任务会插入在收尾内容之前
+pub fn detect_table_end(sheet: &Worksheet, start_row: u32, last_column: u32) -> Option<u32> {
+    let merged_rows: Vec<u32> = sheet
+        .get_merge_cells()
+        .iter()
+        .filter_map(|range| parse_range(&range.get_range()).ok())
+        .filter(|((start_col, _), (end_col, _))| *start_col <= 1 && *end_col >= last_column)
+        .map(|((_, row), _)| row)
+        .collect();
+    let highest = sheet.get_highest_row().min(start_row + MAX_TABLE_ROWS);
+    (start_row..=highest)
+        .find(|row| {
+            merged_rows.contains(row)
+                || (1..=last_column).any(|col| {
+                    let text = sheet.get_value((col, *row));
+                    FOOTER_KEYWORDS.iter().any(|k| text.contains(k))
+                })
+        })
+        .map(|row| row - 1)
+}
+
+/// 在 `at_row` 之前插入 `count` 行，新行沿用 `source_row`（插入后的行号）的样式、公式、行高和行内合并单元格
+///
+/// 插入位置之后的单元格和合并区域由 umya-spreadsheet 整体下移
+pub fn insert_table_rows(sheet: &mut Worksheet, at_row: u32, count: u32, source_row: u32) {
+    if count == 0 {
+        return;
+    }
+    sheet.insert_new_row(&at_row, &count);
+    let source_row = if source_row >= at_row { source_row + count } else { source_row };
+
+    let height = sheet.get_row_dimension(&source_row).map(|row| *row.get_height());
+    let row_merges: Vec<(u32, u32)> = sheet
+        .get_merge_cells()
+        .iter()
+        .filter_map(|range| parse_range(&range.get_range()).ok())
+        .filter(|((_, start), (_, end))| *start == source_row && *end == source_row)
+        .map(|((start_col, _), (end_col, _))| (start_col, end_col))
+        .collect();
+    let cells: Vec<(u32, _, String)> = (1..=sheet.get_highest_column())
+        .filter_map(|col| {
+            sheet
+                .get_cell((col, source_row))
+                .map(|cell| (col, cell.get_style().clone(), cell.get_formula().to_string()))
+        })
+        .collect();
+
+    for row in at_row..at_row + count {
+        let delta = row as i64 - source_row as i64;
+        for (col, style, formula) in &cells {
+            let cell = sheet.get_cell_mut((*col, row));
+            cell.set_style(style.clone());
+            if !formula.is_empty() {
+                cell.set_formula(shift_formula_rows(formula, delta));
+            }
+        }
+        for (start_col, end_col) in &row_merges {
+            sheet.add_merge_cells(format!("{}{}:{}{}", column_name(*start_col), row, column_name(*end_col), row));
+        }
+        if let Some(height) = height {
+            sheet.get_row_dimension_mut(&row).set_height(height);
+        }
+    }
+}
+
+/// 把公式中覆盖表格的区域扩展到新的最后一行
+///
+/// 只修改从表格第一行或更上方开始、结束于原最后一行到新最后一行之间的区域，已经扩展过的区域保持不变
+pub fn expand_formula_ranges(formula: &str, start_row: u32, old_end: u32, new_end: u32) -> String {
+    let range = Regex::new(r"(\$?[A-Za-z]{1,3}\$?)(\d+):(\$?[A-Za-z]{1,3}\$?)(\d+)").unwrap();
+    range
+        .replace_all(formula, |caps: &regex::Captures| {
+            let first: u32 = caps[2].parse().unwrap_or(0);
+            let last: u32 = caps[4].parse().unwrap_or(0);
+            if first <= start_row && last >= old_end && last < new_end {
+                format!("{}{}:{}{}", &caps[1], first, &caps[3], new_end)
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+/// 插入新行后更新工作表中汇总公式（如合计行的 SUM）的区域，使其覆盖新插入的行
+pub fn expand_table_formulas(sheet: &mut Worksheet, start_row: u32, old_end: u32, new_end: u32) {
+    let formulas: Vec<((u32, u32), String)> = sheet
+        .get_cell_collection()
+        .iter()
+        .filter(|cell| !cell.get_formula().is_empty())
+        .map(|cell| {
+            let coordinate = cell.get_coordinate();
+            ((*coordinate.get_col_num(), *coordinate.get_row_num()), cell.get_formula().to_string())
+        })
+        .collect();
+    for (position, formula) in formulas {
+        let expanded = expand_formula_ranges(&formula, start_row, old_end, new_end);
+        if expanded != formula {
+            sheet.get_cell_mut(position).set_formula(expanded);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{Datelike, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use super::header::HeaderDetection;
+
+/// 工作表概况，`header` 为识别到的表头（没有识别到时为空）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SheetInfo {
+    pub index: usize,
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+    pub header: Option<HeaderDetection>,
+}
+
+/// 导出到模板时的工作表选项
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SheetExportOptions {
+    // 模板中要填写的工作表，支持名称或通配符（如 "本周*"），未设置时使用映射中的工作表或第一个工作表
+    pub sheet: Option<String>,
+    // 为 true 时把填好的工作表追加到已有的输出文件中，而不是覆盖整个文件
+    pub append: bool,
+    // 追加的工作表名称，默认为当前ISO周（如 "2025-W24"），同名工作表会被替换
+    pub sheet_name: Option<String>,
+}
+
+/// 名称完全相同，或符合通配符（`*` 任意字符，`?` 单个字符）
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim();
+    if name == pattern {
+        return true;
+    }
+    if !pattern.contains(['*', '?']) {
+        return false;
+    }
+    let regex = regex::escape(pattern).replace(r"\*", ".*").replace(r"\?", ".");
+    Regex::new(&format!("^{}$", regex)).is_ok_and(|re| re.is_match(name))
+}
+
+/// 按名称或通配符选择工作表，返回第一个匹配的下标
+pub fn find_sheet(names: &[String], pattern: &str) -> Result<usize, String> {
+    names
+        .iter()
+        .position(|name| matches_pattern(name, pattern))
+        .ok_or_else(|| format!("没有匹配的工作表: {}（现有工作表: {}）", pattern, names.join("、")))
+}
+
+/// 未指定时选择第一个工作表，返回工作表名称
+pub fn select_sheet(names: &[String], pattern: Option<&str>) -> Result<String, String> {
+    match pattern.filter(|p| !p.trim().is_empty()) {
+        Some(pattern) => find_sheet(names, pattern).map(|index| names[index].clone()),
+        None => names.first().cloned().ok_or_else(|| "模板中没有工作表".to_string()),
+    }
+}
+
+/// 追加工作表时使用的名称：去掉Excel不允许的字符并截断到31个字符
+pub fn new_sheet_name(name: Option<&str>) -> String {
+    let name = match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => {
+            let week = Local::now().date_naive().iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+    };
+    name.chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(31)
+        .collect()
+}
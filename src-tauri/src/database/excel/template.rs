@@ -1,7 +1,7 @@
 use std::fs;
 use std::collections::HashMap;
 use crate::database::types::{Task, ReportTemplate};
-use crate::database::sqlite::init_db;
+use crate::database::sqlite::{init_db, load_latest_dailies_between};
 use crate::database::excel::utils::extract_tasks_from_daily;
 
 pub fn save_report_template(tasks: Vec<Task>, next_week_plan: String) -> Result<(), String> {
@@ -9,6 +9,7 @@ pub fn save_report_template(tasks: Vec<Task>, next_week_plan: String) -> Result<
         tasks,
         next_week_plan,
         column_indices: HashMap::new(),
+        warnings: Vec::new(),
     };
     
     let json = serde_json::to_string_pretty(&template)
@@ -32,33 +33,8 @@ pub fn generate_weekly_from_daily(start_date: String, end_date: String) -> Resul
     // 连接数据库
     let conn = init_db().map_err(|e| e.to_string())?;
     
-    // 查询指定日期范围内的日报
-    let mut stmt = conn.prepare(
-        "SELECT id, date, content, should, done, undone FROM dailies WHERE date BETWEEN ?1 AND ?2 ORDER BY date ASC"
-    ).map_err(|e| e.to_string())?;
-    
-    // 执行查询
-    let daily_rows = stmt.query_map([&start_date, &end_date], |row| {
-        Ok(crate::database::types::DailyReport {
-            id: row.get(0)?,
-            user_id: None,
-            date: row.get(1)?,
-            task_id: None,
-            task_name: None,
-            should_complete: row.get(2).unwrap_or_default(),
-            completed: row.get(3).unwrap_or_default(),
-            uncompleted: row.get(4).unwrap_or_default(),
-            plan_hours: None,
-            actual_hours: None,
-            remarks: row.get(5).unwrap_or_default(),
-        })
-    }).map_err(|e| e.to_string())?;
-    
-    // 收集所有日报
-    let mut daily_reports = Vec::new();
-    for report in daily_rows {
-        daily_reports.push(report.map_err(|e| e.to_string())?);
-    }
+    // 查询指定日期范围内每天最新的一份日报
+    let daily_reports = load_latest_dailies_between(&conn, &start_date, &end_date, None)?;
     
     // 如果没有找到日报，返回错误
     if daily_reports.is_empty() {
@@ -84,5 +60,6 @@ pub fn generate_weekly_from_daily(start_date: String, end_date: String) -> Resul
         tasks,
         next_week_plan,
         column_indices: HashMap::new(),
+        warnings: Vec::new(),
     })
 } 
\ No newline at end of file
@@ -0,0 +1,96 @@
+// 公式行号调整和CSV/文本文件解析的测试，不依赖Excel文件
+use super::rows::{expand_formula_ranges, shift_formula_rows};
+use super::workbook::{decode_text, sniff_format, split_records, FileFormat};
+
+#[test]
+fn shift_relative_rows() {
+    assert_eq!(shift_formula_rows("A2*B2", 3), "A5*B5");
+    assert_eq!(shift_formula_rows("SUM(C2:D2)", 1), "SUM(C3:D3)");
+    assert_eq!(shift_formula_rows("A5-A4", -2), "A3-A2");
+    assert_eq!(shift_formula_rows("A2*B2", 0), "A2*B2");
+}
+
+#[test]
+fn shift_keeps_absolute_rows() {
+    assert_eq!(shift_formula_rows("$A$2+A$2+$A2", 1), "$A$2+A$2+$A3");
+    assert_eq!(shift_formula_rows("VLOOKUP(A2,$E$1:$F$10,2,0)", 2), "VLOOKUP(A4,$E$1:$F$10,2,0)");
+}
+
+#[test]
+fn shift_skips_strings_and_names() {
+    assert_eq!(shift_formula_rows(r#"IF(A2="A2","A2",B2)"#, 1), r#"IF(A3="A2","A2",B3)"#);
+    assert_eq!(shift_formula_rows("LOG10(A1)", 1), "LOG10(A2)");
+    assert_eq!(shift_formula_rows("Sheet1!A1+A1", 1), "Sheet1!A2+A2");
+}
+
+#[test]
+fn shift_never_moves_above_first_row() {
+    assert_eq!(shift_formula_rows("A1+A3", -2), "A1+A1");
+}
+
+#[test]
+fn expand_ranges_ending_in_the_table() {
+    assert_eq!(expand_formula_ranges("SUM(C3:C5)", 3, 5, 8), "SUM(C3:C8)");
+    assert_eq!(expand_formula_ranges("SUM($C$3:$C$5)", 3, 5, 8), "SUM($C$3:$C$8)");
+    assert_eq!(expand_formula_ranges("SUM(C1:C5)+SUM(D3:D5)", 3, 5, 8), "SUM(C1:C8)+SUM(D3:D8)");
+}
+
+#[test]
+fn expand_leaves_other_ranges() {
+    // 从表格中间开始、在原结尾之前结束、或已经扩展过的区域都不变
+    assert_eq!(expand_formula_ranges("SUM(C4:C5)", 3, 5, 8), "SUM(C4:C5)");
+    assert_eq!(expand_formula_ranges("SUM(C3:C4)", 3, 5, 8), "SUM(C3:C4)");
+    assert_eq!(expand_formula_ranges("SUM(C3:C8)", 3, 5, 8), "SUM(C3:C8)");
+    assert_eq!(expand_formula_ranges("SUM(C3:C20)", 3, 5, 8), "SUM(C3:C20)");
+}
+
+#[test]
+fn sniff_binary_formats() {
+    let mut xls = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+    xls.extend_from_slice(&[0; 32]);
+    assert_eq!(sniff_format(&xls), Ok(FileFormat::Xls));
+
+    let zip = |name: &[u8]| {
+        let mut data = vec![0x50, 0x4B, 0x03, 0x04];
+        data.extend_from_slice(&[0; 26]);
+        data.extend_from_slice(name);
+        data
+    };
+    assert_eq!(sniff_format(&zip(b"mimetypeapplication/vnd.oasis.opendocument.spreadsheet")), Ok(FileFormat::Ods));
+    assert_eq!(sniff_format(&zip(b"xl/workbook.bin")), Ok(FileFormat::Xlsb));
+    assert_eq!(sniff_format(&zip(b"xl/workbook.xml")), Ok(FileFormat::Xlsx));
+}
+
+#[test]
+fn sniff_text_formats() {
+    assert_eq!(sniff_format("任务,状态\n".as_bytes()), Ok(FileFormat::Csv));
+    assert_eq!(sniff_format(&[0xFF, 0xFE, b'a', 0, b',', 0, b'b', 0]), Ok(FileFormat::Csv));
+    assert!(sniff_format(&[0x7F, b'E', b'L', b'F', 0, 0, 0]).is_err());
+}
+
+#[test]
+fn decode_utf8_utf16_and_gbk() {
+    assert_eq!(decode_text("任务".as_bytes()), "任务");
+    assert_eq!(decode_text(&[0xEF, 0xBB, 0xBF, b'a', b',', b'b']), "a,b");
+    assert_eq!(decode_text(&[0xFF, 0xFE, 0xFB, 0x4E, 0xA1, 0x52]), "任务");
+    // GBK编码的"任务,完成"
+    assert_eq!(decode_text(&[0xC8, 0xCE, 0xCE, 0xF1, b',', 0xCD, 0xEA, 0xB3, 0xC9]), "任务,完成");
+}
+
+#[test]
+fn split_plain_records() {
+    assert_eq!(split_records("a,b\nc,d\n", ','), vec![vec!["a", "b"], vec!["c", "d"]]);
+    assert_eq!(split_records("a,b\r\nc,d", ','), vec![vec!["a", "b"], vec!["c", "d"]]);
+    assert_eq!(split_records("a\t\tb", '\t'), vec![vec!["a", "", "b"]]);
+    assert_eq!(split_records("a,\n", ','), vec![vec!["a", ""]]);
+}
+
+#[test]
+fn split_quoted_fields() {
+    assert_eq!(
+        split_records("\"修复,登录\",\"第一行\n第二行\"\n\"说\"\"好\"\"\",x", ','),
+        vec![vec!["修复,登录", "第一行\n第二行"], vec!["说\"好\"", "x"]]
+    );
+    // 字段中间的引号按普通字符处理
+    assert_eq!(split_records("5\"屏,ok", ','), vec![vec!["5\"屏", "ok"]]);
+}
@@ -1,4 +1,5 @@
 use crate::database::types::Task;
+use crate::database::tickets::TicketExtractor;
 
 // 辅助函数：从文本中提取任务项（按行分割）
 pub fn extract_task_items(text: &str) -> Vec<String> {
@@ -32,6 +33,8 @@ pub fn extract_task_items(text: &str) -> Vec<String> {
 pub fn extract_tasks_from_daily(daily_reports: &[crate::database::types::DailyReport]) -> Result<Vec<Task>, String> {
     let mut tasks: Vec<Task> = Vec::new();
     let mut task_map: std::collections::HashMap<String, Task> = std::collections::HashMap::new();
+    // 任务内容中引用的编号（如 PROJ-123、#456）优先于日报级别的 task_id
+    let extractor = TicketExtractor::from_config();
     
     // 遍历所有日报，提取和合并任务
     for report in daily_reports {
@@ -41,11 +44,12 @@ pub fn extract_tasks_from_daily(daily_reports: &[crate::database::types::DailyRe
             let task_key = item.clone();
             if !task_map.contains_key(&task_key) {
                 // 新任务
+                let task_id = extractor.task_id_for(&item).or_else(|| report.task_id.clone());
                 task_map.insert(task_key.clone(), Task {
                     task: item,
                     status: "进行中".to_string(),
                     remarks: String::new(),
-                    task_id: report.task_id.clone(),
+                    task_id,
                     task_name: report.task_name.clone(),
                     plan_start_time: Some(report.date.clone()),
                     plan_end_time: Some(report.date.clone()),
@@ -72,11 +76,12 @@ pub fn extract_tasks_from_daily(daily_reports: &[crate::database::types::DailyRe
                 task_map.insert(task_key, task);
             } else {
                 // 如果是新任务，添加为已完成
+                let task_id = extractor.task_id_for(&item).or_else(|| report.task_id.clone());
                 task_map.insert(task_key.clone(), Task {
                     task: item,
                     status: "已完成".to_string(),
                     remarks: String::new(),
-                    task_id: report.task_id.clone(),
+                    task_id,
                     task_name: report.task_name.clone(),
                     plan_start_time: Some(report.date.clone()),
                     plan_end_time: Some(report.date.clone()),
@@ -102,11 +107,12 @@ pub fn extract_tasks_from_daily(daily_reports: &[crate::database::types::DailyRe
                 task_map.insert(task_key, task);
             } else {
                 // 如果是新任务，添加为未完成
+                let task_id = extractor.task_id_for(&item).or_else(|| report.task_id.clone());
                 task_map.insert(task_key.clone(), Task {
                     task: item,
                     status: "进行中".to_string(),
                     remarks: format!("{}未能完成", report.date),
-                    task_id: report.task_id.clone(),
+                    task_id,
                     task_name: report.task_name.clone(),
                     plan_start_time: Some(report.date.clone()),
                     plan_end_time: None,
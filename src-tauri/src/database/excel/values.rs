@@ -0,0 +1,183 @@
+use chrono::NaiveDate;
+use umya_spreadsheet::{Cell, Worksheet};
+use super::mapping::parse_range;
+
+// 日期字段：导出时写为Excel日期，导入时统一为 yyyy-mm-dd
+pub const DATE_FIELDS: [&str; 5] = ["plan_start_time", "plan_end_time", "actual_start_time", "actual_end_time", "date"];
+// 工时字段：按小时数读写
+pub const HOUR_FIELDS: [&str; 2] = ["plan_hours", "actual_hours"];
+// 单元格没有设置格式时日期使用的格式
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+
+// 任务状态的统一写法及常见的其他写法
+const STATUS_SYNONYMS: [(&str, &[&str]); 5] = [
+    ("未开始", &["未开始", "待开始", "未启动", "待处理", "todo", "not started"]),
+    ("进行中", &["进行中", "处理中", "开发中", "未完成", "进行", "doing", "in progress"]),
+    ("已完成", &["已完成", "完成", "已结束", "结束", "已上线", "done", "finished", "completed"]),
+    ("已暂停", &["已暂停", "暂停", "搁置", "挂起", "paused", "on hold"]),
+    ("已取消", &["已取消", "取消", "作废", "cancelled", "canceled"]),
+];
+
+/// 写入单元格的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Empty,
+}
+
+/// 解析常见的日期写法："2025-06-09"、"2025/6/9"、"2025.06.09"、"2025年6月9日"，忽略后面的时间
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    let date = text.split([' ', 'T']).next().unwrap_or_default();
+    let normalized = date
+        .replace(['/', '.', '年', '月'], "-")
+        .trim_end_matches('日')
+        .to_string();
+    NaiveDate::parse_from_str(&normalized, "%Y-%m-%d").ok()
+}
+
+/// 解析工时，支持 "2"、"1.5h"、"2小时"、"3 hrs"、"0.5天"（按8小时计）
+pub fn parse_hours(text: &str) -> Option<f64> {
+    let text = text.trim().to_lowercase();
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let value: f64 = text[..end].parse().ok()?;
+    let unit = text[end..].trim();
+    match unit {
+        "" | "h" | "hr" | "hrs" | "hour" | "hours" | "小时" | "个小时" | "时" => Some(value),
+        "d" | "day" | "days" | "天" | "人天" => Some(value * 8.0),
+        "m" | "min" | "mins" | "分钟" => Some(value / 60.0),
+        _ => None,
+    }
+}
+
+/// 把状态的各种写法统一为 未开始/进行中/已完成/已暂停/已取消
+pub fn normalize_status(text: &str) -> Option<&'static str> {
+    let text = text.trim().to_lowercase();
+    STATUS_SYNONYMS
+        .iter()
+        .find(|(_, synonyms)| synonyms.contains(&text.as_str()))
+        .map(|(status, _)| *status)
+}
+
+/// Excel日期序列号（1900日期系统），1899-12-30 为0
+pub fn excel_serial(date: NaiveDate) -> f64 {
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    (date - base).num_days() as f64
+}
+
+/// 把Excel日期序列号转换为日期，忽略小数部分的时间；超出Excel支持范围时返回 None
+pub fn serial_to_date(serial: f64) -> Option<NaiveDate> {
+    if !(1.0..2958466.0).contains(&serial) {
+        return None;
+    }
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    base.checked_add_signed(chrono::Duration::days(serial.floor() as i64))
+}
+
+/// 数字转为文字，整数不带小数点，其他最多保留两位小数
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        let text = format!("{:.2}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// 按字段确定写入的类型：日期字段写为Excel日期，工时字段写为数字，无法解析时按原文写入
+pub fn typed_value(field: &str, text: &str) -> CellValue {
+    if text.trim().is_empty() {
+        return CellValue::Empty;
+    }
+    if DATE_FIELDS.contains(&field) {
+        if let Some(date) = parse_date(text) {
+            return CellValue::Date(date);
+        }
+    }
+    if HOUR_FIELDS.contains(&field) {
+        if let Some(hours) = parse_hours(text) {
+            return CellValue::Number(hours);
+        }
+    }
+    CellValue::Text(text.to_string())
+}
+
+/// 写入单元格，保留模板中的样式；日期单元格没有设置格式时使用 yyyy-mm-dd
+pub fn write_typed(cell: &mut Cell, value: &CellValue) {
+    match value {
+        CellValue::Text(text) => {
+            cell.set_value(text.clone());
+        }
+        CellValue::Number(number) => {
+            cell.set_value_number(*number);
+        }
+        CellValue::Date(date) => {
+            let has_format = cell
+                .get_style()
+                .get_number_format()
+                .is_some_and(|f| !f.get_format_code().is_empty() && f.get_format_code() != "General");
+            if !has_format {
+                cell.get_style_mut().get_number_format_mut().set_format_code(DATE_FORMAT);
+            }
+            cell.set_value_number(excel_serial(*date));
+        }
+        CellValue::Empty => {
+            cell.set_value("");
+        }
+    }
+}
+
+/// 读取覆盖某个单元格的下拉列表（数据验证）中的可选值
+///
+/// 支持直接写在验证中的 `"a,b,c"` 和引用本工作表单元格区域的列表
+pub fn validation_list(sheet: &Worksheet, position: (u32, u32)) -> Option<Vec<String>> {
+    let (col, row) = position;
+    let validation = sheet
+        .get_data_validations()?
+        .get_data_validation_list()
+        .iter()
+        .find(|v| {
+            v.get_sequence_of_references()
+                .get_sqref()
+                .split_whitespace()
+                .filter_map(|range| parse_range(range).ok())
+                .any(|((c1, r1), (c2, r2))| (c1..=c2).contains(&col) && (r1..=r2).contains(&row))
+        })?;
+
+    let formula = validation.get_formula1().trim().trim_start_matches('=');
+    if let Some(list) = formula.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+        return Some(list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect());
+    }
+    if formula.contains('!') {
+        eprintln!("下拉列表引用了其他工作表，跳过检查: {}", formula);
+        return None;
+    }
+    let ((c1, r1), (c2, r2)) = parse_range(formula).ok()?;
+    let mut values = Vec::new();
+    for r in r1..=r2 {
+        for c in c1..=c2 {
+            let value = sheet.get_value((c, r)).trim().to_string();
+            if !value.is_empty() {
+                values.push(value);
+            }
+        }
+    }
+    Some(values)
+}
+
+/// 把状态对应到下拉列表中的选项：完全相同，或统一写法后相同
+pub fn match_option(status: &str, options: &[String]) -> Option<String> {
+    let status = status.trim();
+    if let Some(option) = options.iter().find(|o| o.as_str() == status) {
+        return Some(option.clone());
+    }
+    let canonical = normalize_status(status)?;
+    options
+        .iter()
+        .find(|o| normalize_status(o) == Some(canonical))
+        .cloned()
+}
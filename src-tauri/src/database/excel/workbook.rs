@@ -0,0 +1,176 @@
+use calamine::{Cell, DataType, Ods, Range, Reader, Sheets, Xls, Xlsb, Xlsx};
+use encoding_rs::{Encoding, GB18030};
+use std::io::Cursor;
+
+// 文本文件导入后的工作表名称
+const CSV_SHEET: &str = "CSV";
+
+const OLE_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+
+/// 导入文件的格式，按文件开头的内容判断，不依赖扩展名
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    Xlsx,
+    Xlsb,
+    Xls,
+    Ods,
+    Csv,
+}
+
+/// 判断文件格式：xls 为OLE复合文档，xlsx/xlsb/ods 为zip包，其余按文本表格处理
+pub fn sniff_format(data: &[u8]) -> Result<FileFormat, String> {
+    if data.starts_with(&OLE_MAGIC) {
+        return Ok(FileFormat::Xls);
+    }
+    if data.starts_with(&ZIP_MAGIC) {
+        // ods 的第一个文件是不压缩的 mimetype，xlsb 的工作簿是 workbook.bin
+        if contains(&data[..data.len().min(256)], b"application/vnd.oasis.opendocument.spreadsheet") {
+            return Ok(FileFormat::Ods);
+        }
+        if contains(data, b"xl/workbook.bin") {
+            return Ok(FileFormat::Xlsb);
+        }
+        return Ok(FileFormat::Xlsx);
+    }
+    let head = &data[..data.len().min(1024)];
+    let utf16 = head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]);
+    if !utf16 && head.contains(&0) {
+        return Err("无法识别的文件格式，请使用 xlsx、xls、ods 或 CSV 文件".to_string());
+    }
+    Ok(FileFormat::Csv)
+}
+
+fn contains(data: &[u8], pattern: &[u8]) -> bool {
+    data.windows(pattern.len()).any(|window| window == pattern)
+}
+
+/// 打开的表格文件，Excel/WPS 文件通过 calamine 读取，CSV/TSV 只有一个工作表
+pub enum Workbook {
+    Sheets(Sheets<Cursor<Vec<u8>>>),
+    Csv(Range<DataType>),
+}
+
+impl Workbook {
+    pub fn open(data: Vec<u8>) -> Result<Self, String> {
+        let format = sniff_format(&data)?;
+        let cursor = Cursor::new(data);
+        let sheets = match format {
+            FileFormat::Xlsx => Sheets::Xlsx(Xlsx::new(cursor).map_err(|e| format!("打开Excel文件失败: {}", e))?),
+            FileFormat::Xlsb => Sheets::Xlsb(Xlsb::new(cursor).map_err(|e| format!("打开Excel文件失败: {}", e))?),
+            FileFormat::Xls => Sheets::Xls(Xls::new(cursor).map_err(|e| format!("打开xls文件失败: {}", e))?),
+            FileFormat::Ods => Sheets::Ods(Ods::new(cursor).map_err(|e| format!("打开ods文件失败: {}", e))?),
+            FileFormat::Csv => return Ok(Workbook::Csv(parse_csv(&cursor.into_inner()))),
+        };
+        Ok(Workbook::Sheets(sheets))
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        match self {
+            Workbook::Sheets(sheets) => sheets.sheet_names().to_vec(),
+            Workbook::Csv(_) => vec![CSV_SHEET.to_string()],
+        }
+    }
+
+    pub fn sheet_range(&mut self, sheet_name: &str) -> Result<Range<DataType>, String> {
+        match self {
+            // 正确处理Option<Result<Range, Error>>
+            Workbook::Sheets(sheets) => match sheets.worksheet_range(sheet_name) {
+                Some(Ok(range)) => Ok(range),
+                Some(Err(e)) => Err(format!("读取工作表出错: {}", e)),
+                None => Err(format!("没有找到工作表: {}", sheet_name)),
+            },
+            Workbook::Csv(range) if sheet_name == CSV_SHEET => Ok(range.clone()),
+            Workbook::Csv(_) => Err(format!("没有找到工作表: {}", sheet_name)),
+        }
+    }
+}
+
+/// 按BOM或内容判断文本编码：UTF-8、UTF-16（Excel的"Unicode文本"），都不是时按GBK（GB18030）解码
+pub fn decode_text(data: &[u8]) -> String {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(data) {
+        let (text, _) = encoding.decode_without_bom_handling(&data[bom_length..]);
+        return text.into_owned();
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        return text.to_string();
+    }
+    eprintln!("文件不是UTF-8编码，按GBK解码");
+    GB18030.decode_without_bom_handling(data).0.into_owned()
+}
+
+// 根据第一行（引号外）出现次数最多的分隔符判断是CSV、TSV还是分号分隔
+fn detect_delimiter(text: &str) -> char {
+    let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+    let mut counts = [('\t', 0), (',', 0), (';', 0)];
+    let mut quoted = false;
+    for c in first_line.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if !quoted {
+            if let Some(count) = counts.iter_mut().find(|(d, _)| *d == c) {
+                count.1 += 1;
+            }
+        }
+    }
+    counts
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count)
+        .map_or(',', |(d, _)| *d)
+}
+
+/// 把CSV/TSV文本拆分为行和单元格，支持引号中的分隔符、换行和 `""` 转义
+pub fn split_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// 把CSV/TSV文件读取为与Excel工作表相同的单元格区域，单元格均为文字
+pub fn parse_csv(data: &[u8]) -> Range<DataType> {
+    let text = decode_text(data);
+    let delimiter = detect_delimiter(&text);
+    let cells: Vec<Cell<DataType>> = split_records(&text, delimiter)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(row, record)| {
+            record
+                .into_iter()
+                .enumerate()
+                .filter(|(_, value)| !value.trim().is_empty())
+                .map(move |(col, value)| Cell::new((row as u32, col as u32), DataType::String(value)))
+        })
+        .collect();
+    Range::from_sparse(cells)
+}
@@ -0,0 +1,140 @@
+use crate::database::types::{ReportTemplate, UserInfo};
+use crate::database::sqlite::{init_db, load_latest_dailies_between};
+use crate::database::excel::utils::extract_task_items;
+use crate::database::calendar::month_range;
+
+/// 表格，`widths` 为各列的相对宽度
+pub struct Table {
+    pub headers: Vec<String>,
+    pub widths: Vec<u32>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub enum Block {
+    Heading(String),
+    Paragraph(String),
+    Table(Table),
+}
+
+/// 与输出格式无关的报告内容，DOCX和PDF导出共用
+pub struct ReportDocument {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub blocks: Vec<Block>,
+    // Word模板中 {{name}} 占位符对应的文本
+    pub placeholders: Vec<(String, String)>,
+}
+
+impl ReportDocument {
+    /// 第一个表格，Word模板中的 {{tasks}} 会替换为该表格
+    pub fn main_table(&self) -> Option<&Table> {
+        self.blocks.iter().find_map(|b| match b {
+            Block::Table(table) => Some(table),
+            _ => None,
+        })
+    }
+}
+
+fn to_strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+/// 周报：本周任务表格和下周计划
+pub fn weekly_document(start_date: &str, end_date: &str, report: &ReportTemplate) -> ReportDocument {
+    let rows = report
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            vec![
+                (i + 1).to_string(),
+                task.task_id.clone().unwrap_or_default(),
+                task.task.clone(),
+                task.status.clone(),
+                task.plan_hours.clone().unwrap_or_default(),
+                task.actual_hours.clone().unwrap_or_default(),
+                task.remarks.clone(),
+            ]
+        })
+        .collect();
+    let table = Table {
+        headers: to_strings(&["序号", "任务编号", "任务内容", "状态", "计划工时", "实际工时", "备注"]),
+        widths: vec![1, 2, 6, 2, 2, 2, 3],
+        rows,
+    };
+    let title = format!("周报（{} 至 {}）", start_date, end_date);
+
+    ReportDocument {
+        title: title.clone(),
+        subtitle: None,
+        blocks: vec![
+            Block::Heading("（一）本周工作".to_string()),
+            Block::Table(table),
+            Block::Heading("（二）下周工作计划".to_string()),
+            Block::Paragraph(report.next_week_plan.clone()),
+        ],
+        placeholders: vec![
+            ("title".to_string(), title),
+            ("start_date".to_string(), start_date.to_string()),
+            ("end_date".to_string(), end_date.to_string()),
+            ("next_week_plan".to_string(), report.next_week_plan.clone()),
+        ],
+    }
+}
+
+/// 月报：按本地日报汇总当月每天的工作，未完成事项作为下月计划
+///
+/// 同一天保存了多份日报时只使用最后一份非空日报，`user_id` 为空时不按用户过滤
+pub fn monthly_document(year_month: &str, user_info: &UserInfo, user_id: Option<&str>) -> Result<ReportDocument, String> {
+    let (first, last) = month_range(year_month)?;
+
+    let conn = init_db().map_err(|e| e.to_string())?;
+    let dailies = load_latest_dailies_between(
+        &conn,
+        &first.format("%Y-%m-%d").to_string(),
+        &last.format("%Y-%m-%d").to_string(),
+        user_id,
+    )?;
+
+    let rows = dailies
+        .iter()
+        .map(|r| vec![r.date.clone(), r.completed.clone(), r.uncompleted.clone(), r.remarks.clone()])
+        .collect();
+    let mut plan_items: Vec<String> = Vec::new();
+    for report in &dailies {
+        for item in extract_task_items(&report.uncompleted) {
+            if !plan_items.contains(&item) {
+                plan_items.push(item);
+            }
+        }
+    }
+    let next_month_plan = plan_items.join("\n");
+
+    let title = format!("{} 月报", year_month);
+    let subtitle = format!(
+        "姓名：{}    部门：{}    岗位：{}",
+        user_info.name, user_info.department, user_info.position
+    );
+    Ok(ReportDocument {
+        title: title.clone(),
+        subtitle: Some(subtitle),
+        blocks: vec![
+            Block::Heading("（一）本月工作".to_string()),
+            Block::Table(Table {
+                headers: to_strings(&["日期", "已完成", "未完成", "备注"]),
+                widths: vec![2, 6, 4, 3],
+                rows,
+            }),
+            Block::Heading("（二）下月工作计划".to_string()),
+            Block::Paragraph(next_month_plan.clone()),
+        ],
+        placeholders: vec![
+            ("title".to_string(), title),
+            ("year_month".to_string(), year_month.to_string()),
+            ("name".to_string(), user_info.name.clone()),
+            ("department".to_string(), user_info.department.clone()),
+            ("position".to_string(), user_info.position.clone()),
+            ("next_month_plan".to_string(), next_month_plan),
+        ],
+    })
+}
@@ -0,0 +1,217 @@
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use regex::Regex;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use super::document::{Block, ReportDocument, Table};
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#;
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+// A4纸张，上下左右各2.54厘米页边距
+const SECTION_XML: &str = r#"<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr>"#;
+// 版心宽度（twip）
+const TEXT_WIDTH: u32 = 9026;
+const FONT_NAME: &str = "宋体";
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// 一段文字，多行文本用换行符 <w:br/> 分隔
+fn run_xml(text: &str, run_props: &str) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape_xml(line)))
+        .collect();
+    format!("<w:r>{}{}</w:r>", run_props, lines.join("<w:br/>"))
+}
+
+fn run_props(bold: bool, size: u32) -> String {
+    format!(
+        r#"<w:rPr><w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:eastAsia="{font}"/>{}<w:sz w:val="{size}"/><w:szCs w:val="{size}"/></w:rPr>"#,
+        if bold { "<w:b/>" } else { "" },
+        font = FONT_NAME,
+        size = size,
+    )
+}
+
+// size 以半磅为单位，24 即小四号
+fn paragraph_xml(text: &str, bold: bool, size: u32, center: bool) -> String {
+    let align = if center { r#"<w:pPr><w:jc w:val="center"/></w:pPr>"# } else { "" };
+    format!("<w:p>{}{}</w:p>", align, run_xml(text, &run_props(bold, size)))
+}
+
+fn table_xml(table: &Table) -> String {
+    let total: u32 = table.widths.iter().sum::<u32>().max(1);
+    let widths: Vec<u32> = (0..table.headers.len())
+        .map(|i| TEXT_WIDTH * table.widths.get(i).copied().unwrap_or(1) / total)
+        .collect();
+    let borders = ["top", "left", "bottom", "right", "insideH", "insideV"]
+        .iter()
+        .map(|side| format!(r#"<w:{} w:val="single" w:sz="4" w:space="0" w:color="000000"/>"#, side))
+        .collect::<String>();
+
+    let cell = |text: &str, width: u32, header: bool| {
+        let shading = if header { r#"<w:shd w:val="clear" w:color="auto" w:fill="D9E2F3"/>"# } else { "" };
+        format!(
+            r#"<w:tc><w:tcPr><w:tcW w:w="{}" w:type="dxa"/>{}</w:tcPr>{}</w:tc>"#,
+            width,
+            shading,
+            paragraph_xml(text, header, 21, header)
+        )
+    };
+    let row = |cells: &[String], header: bool| {
+        let cells: String = cells
+            .iter()
+            .zip(&widths)
+            .map(|(text, width)| cell(text, *width, header))
+            .collect();
+        let repeat = if header { "<w:trPr><w:tblHeader/></w:trPr>" } else { "" };
+        format!("<w:tr>{}{}</w:tr>", repeat, cells)
+    };
+
+    let mut xml = format!(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="{}" w:type="dxa"/><w:tblBorders>{}</w:tblBorders></w:tblPr><w:tblGrid>{}</w:tblGrid>"#,
+        TEXT_WIDTH,
+        borders,
+        widths.iter().map(|w| format!(r#"<w:gridCol w:w="{}"/>"#, w)).collect::<String>()
+    );
+    xml.push_str(&row(&table.headers, true));
+    for cells in &table.rows {
+        xml.push_str(&row(cells, false));
+    }
+    xml.push_str("</w:tbl>");
+    xml
+}
+
+fn body_xml(doc: &ReportDocument) -> String {
+    let mut xml = String::new();
+    for block in &doc.blocks {
+        match block {
+            Block::Heading(text) => xml.push_str(&paragraph_xml(text, true, 28, false)),
+            Block::Paragraph(text) => xml.push_str(&paragraph_xml(text, false, 24, false)),
+            // 表格后面必须跟一个段落，否则相邻的两个表格会被Word合并
+            Block::Table(table) => {
+                xml.push_str(&table_xml(table));
+                xml.push_str("<w:p/>");
+            }
+        }
+    }
+    xml
+}
+
+fn document_xml(doc: &ReportDocument) -> String {
+    let mut body = paragraph_xml(&doc.title, true, 36, true);
+    if let Some(subtitle) = &doc.subtitle {
+        body.push_str(&paragraph_xml(subtitle, false, 24, true));
+    }
+    body.push_str(&body_xml(doc));
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}{}</w:body></w:document>"#,
+        body, SECTION_XML
+    )
+}
+
+/// 替换Word文档XML中的占位符
+///
+/// Word经常把 `{{name}}` 拆到多个文字段里，所以按段落拼接文字后再替换，替换后的段落沿用第一段文字的格式。
+/// 独占一段的 `{{tasks}}` 替换为任务表格，`{{body}}` 替换为完整的报告内容。
+fn fill_placeholders(xml: &str, doc: &ReportDocument) -> String {
+    let paragraph_re = Regex::new(r"(?s)<w:p(?: [^>]*[^/])?>.*?</w:p>").unwrap();
+    let text_re = Regex::new(r"(?s)<w:t(?: [^>]*)?>(.*?)</w:t>").unwrap();
+    let props_re = Regex::new(r"(?s)<w:pPr>.*?</w:pPr>").unwrap();
+    let run_props_re = Regex::new(r"(?s)<w:rPr>.*?</w:rPr>").unwrap();
+
+    paragraph_re
+        .replace_all(xml, |caps: &regex::Captures| {
+            let paragraph = &caps[0];
+            let text: String = text_re
+                .captures_iter(paragraph)
+                .map(|c| unescape_xml(&c[1]))
+                .collect();
+            if !text.contains("{{") {
+                return paragraph.to_string();
+            }
+
+            match text.trim() {
+                "{{body}}" => return body_xml(doc),
+                // 表格后补一个空段落：占位符在单元格末尾时 <w:tc> 必须以段落结束，否则Word会提示文件损坏
+                "{{tasks}}" => {
+                    if let Some(table) = doc.main_table() {
+                        return format!("{}<w:p/>", table_xml(table));
+                    }
+                }
+                _ => {}
+            }
+
+            let mut replaced = text.clone();
+            for (name, value) in &doc.placeholders {
+                replaced = replaced.replace(&format!("{{{{{}}}}}", name), value);
+            }
+            if replaced == text {
+                return paragraph.to_string();
+            }
+            let paragraph_props = props_re.find(paragraph);
+            let runs = paragraph_props.map(|m| &paragraph[m.end()..]).unwrap_or(paragraph);
+            let props = run_props_re.find(runs).map(|m| m.as_str()).unwrap_or_default();
+            let paragraph_props = paragraph_props.map(|m| m.as_str()).unwrap_or_default();
+            format!("<w:p>{}{}</w:p>", paragraph_props, run_xml(&replaced, props))
+        })
+        .to_string()
+}
+
+fn write_entry<W: Write + std::io::Seek>(zip: &mut ZipWriter<W>, name: &str, data: &[u8]) -> Result<(), String> {
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(name, options).map_err(|e| format!("写入文档失败: {}", e))?;
+    zip.write_all(data).map_err(|e| format!("写入文档失败: {}", e))
+}
+
+/// 生成DOCX文件内容
+///
+/// 提供 `template_path` 时以该Word文件为模板，替换正文、页眉和页脚中的占位符，其余内容保持不变
+pub fn build_docx(doc: &ReportDocument, template_path: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    match template_path {
+        Some(path) => {
+            let file = fs::File::open(path).map_err(|e| format!("打开Word模板失败: {}", e))?;
+            let mut archive = ZipArchive::new(file).map_err(|e| format!("Word模板格式不正确: {}", e))?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i).map_err(|e| format!("读取Word模板失败: {}", e))?;
+                let name = entry.name().to_string();
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).map_err(|e| format!("读取Word模板失败: {}", e))?;
+
+                let is_text_part = name == "word/document.xml"
+                    || ((name.starts_with("word/header") || name.starts_with("word/footer")) && name.ends_with(".xml"));
+                if is_text_part {
+                    let xml = String::from_utf8_lossy(&data);
+                    data = fill_placeholders(&xml, doc).into_bytes();
+                }
+                write_entry(&mut zip, &name, &data)?;
+            }
+        }
+        None => {
+            write_entry(&mut zip, "[Content_Types].xml", CONTENT_TYPES_XML.as_bytes())?;
+            write_entry(&mut zip, "_rels/.rels", ROOT_RELS_XML.as_bytes())?;
+            write_entry(&mut zip, "word/document.xml", document_xml(doc).as_bytes())?;
+        }
+    }
+
+    let cursor = zip.finish().map_err(|e| format!("生成Word文档失败: {}", e))?;
+    Ok(cursor.into_inner())
+}
@@ -0,0 +1,69 @@
+// Export module - Word/PDF exporters
+mod document;
+mod docx;
+mod pdf;
+
+use std::path::Path;
+use crate::database::types::{ReportTemplate, UserInfo};
+
+fn write_output(output_path: &str, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = Path::new(output_path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败: {}", e))?;
+    }
+    std::fs::write(output_path, data).map_err(|e| format!("保存文件失败: {}", e))
+}
+
+// 导出周报为Word，template_path 为带占位符的 .docx 模板（可选）
+#[tauri::command]
+pub fn export_weekly_docx(
+    start_date: String,
+    end_date: String,
+    report: ReportTemplate,
+    output_path: String,
+    template_path: Option<String>,
+) -> Result<(), String> {
+    let doc = document::weekly_document(&start_date, &end_date, &report);
+    let data = docx::build_docx(&doc, template_path.as_deref())?;
+    write_output(&output_path, &data)
+}
+
+// 导出月报为Word，user_id 为空时汇总所有用户的日报
+#[tauri::command]
+pub fn export_monthly_docx(
+    year_month: String,
+    user_info: UserInfo,
+    output_path: String,
+    template_path: Option<String>,
+    user_id: Option<String>,
+) -> Result<(), String> {
+    let doc = document::monthly_document(&year_month, &user_info, user_id.as_deref())?;
+    let data = docx::build_docx(&doc, template_path.as_deref())?;
+    write_output(&output_path, &data)
+}
+
+// 导出周报为PDF，font_path 为空时自动查找系统中文字体
+#[tauri::command]
+pub fn export_weekly_pdf(
+    start_date: String,
+    end_date: String,
+    report: ReportTemplate,
+    output_path: String,
+    font_path: Option<String>,
+) -> Result<(), String> {
+    let doc = document::weekly_document(&start_date, &end_date, &report);
+    let data = pdf::build_pdf(&doc, font_path.as_deref())?;
+    write_output(&output_path, &data)
+}
+
+#[tauri::command]
+pub fn export_monthly_pdf(
+    year_month: String,
+    user_info: UserInfo,
+    output_path: String,
+    font_path: Option<String>,
+    user_id: Option<String>,
+) -> Result<(), String> {
+    let doc = document::monthly_document(&year_month, &user_info, user_id.as_deref())?;
+    let data = pdf::build_pdf(&doc, font_path.as_deref())?;
+    write_output(&output_path, &data)
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::Path;
+use printpdf::{IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use super::document::{Block, ReportDocument, Table};
+
+// 常见系统中带中文字形的TrueType字体，按顺序查找第一个可用的
+//
+// printpdf 会把整个字体文件嵌入PDF，单个字体的文件优先；字体集合（.ttc）只嵌入其中第一个字体，
+// Noto CJK 等CFF字形的字体无法作为TrueType嵌入，不在候选中
+const FONT_CANDIDATES: [&str; 11] = [
+    "C:/Windows/Fonts/simhei.ttf",
+    "C:/Windows/Fonts/simkai.ttf",
+    "C:/Windows/Fonts/simfang.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    "C:/Windows/Fonts/simsun.ttc",
+    "C:/Windows/Fonts/msyh.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wqy-microhei/wqy-microhei.ttc",
+];
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const PT_TO_MM: f32 = 0.3528;
+const LINE_SPACING: f32 = 1.5;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<usize> {
+    data.get(offset..offset + 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<usize> {
+    data.get(offset..offset + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
+
+// 字体文件中是否有指定的表
+fn has_table(font: &[u8], tag: &[u8]) -> bool {
+    let count = read_u16(font, 4).unwrap_or(0);
+    (0..count).any(|i| font.get(12 + i * 16..16 + i * 16) == Some(tag))
+}
+
+/// 从字体集合（.ttc）中取出第一个字体，重新排列为独立的字体文件
+///
+/// 集合中的表偏移量是相对整个文件的，需要把用到的表复制出来并改写偏移量
+fn extract_first_face(data: &[u8]) -> Option<Vec<u8>> {
+    let face = read_u32(data, 12)?;
+    let count = read_u16(data, face + 4)?;
+    let mut tables = Vec::with_capacity(count);
+    for i in 0..count {
+        let record = face + 12 + i * 16;
+        let offset = read_u32(data, record + 8)?;
+        let length = read_u32(data, record + 12)?;
+        tables.push((data.get(record..record + 8)?, data.get(offset..offset + length)?));
+    }
+
+    let mut font = data.get(face..face + 12)?.to_vec();
+    let mut offset = 12 + count * 16;
+    for (tag_and_checksum, table) in &tables {
+        font.extend_from_slice(tag_and_checksum);
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += (table.len() + 3) & !3;
+    }
+    for (_, table) in &tables {
+        font.extend_from_slice(table);
+        font.resize((font.len() + 3) & !3, 0);
+    }
+    Some(font)
+}
+
+// 读取字体文件，字体集合取第一个字体；只接受TrueType字形的字体
+fn load_font(path: &str) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| format!("读取字体失败: {}", e))?;
+    let font = if data.starts_with(b"ttcf") {
+        extract_first_face(&data).ok_or_else(|| format!("无法读取字体集合: {}", path))?
+    } else {
+        data
+    };
+    if !has_table(&font, b"glyf") {
+        return Err(format!("字体 {} 不是TrueType字形，无法嵌入PDF，请使用 .ttf 字体", path));
+    }
+    Ok(font)
+}
+
+/// 读取用于嵌入PDF的中文字体，优先使用指定的字体文件
+pub fn load_cjk_font(font_path: Option<&str>) -> Result<Vec<u8>, String> {
+    if let Some(path) = font_path.filter(|p| !p.trim().is_empty()) {
+        if !Path::new(path).exists() {
+            return Err(format!("字体文件不存在: {}", path));
+        }
+        return load_font(path);
+    }
+    for path in FONT_CANDIDATES.iter().filter(|p| Path::new(p).exists()) {
+        match load_font(path) {
+            Ok(font) => return Ok(font),
+            Err(e) => eprintln!("跳过字体: {}", e),
+        }
+    }
+    Err("未找到中文字体，请指定TrueType字体文件（.ttf/.ttc）".to_string())
+}
+
+// 按字符估算文字宽度：全角字符占一个字号，半角字符约占一半
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars()
+        .map(|c| if c.is_ascii() { 0.55 } else { 1.0 })
+        .sum::<f32>()
+        * size
+        * PT_TO_MM
+}
+
+// 按可用宽度折行，保留原有的换行
+fn wrap(text: &str, width: f32, size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut line = String::new();
+        for c in raw.chars() {
+            line.push(c);
+            if text_width(&line, size) > width && line.chars().count() > 1 {
+                line.pop();
+                lines.push(std::mem::take(&mut line));
+                line.push(c);
+            }
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// 逐行写入内容，超出页面时自动换页
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    font: IndirectFontRef,
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str, font: &[u8]) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "内容");
+        let font = doc.add_external_font(font).map_err(|e| format!("加载字体失败: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(PdfWriter { doc, font, layer, y: PAGE_HEIGHT - MARGIN })
+    }
+
+    fn line_height(size: f32) -> f32 {
+        size * PT_TO_MM * LINE_SPACING
+    }
+
+    // 剩余空间不足 height 时换页
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "内容");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text_at(&self, text: &str, size: f32, x: f32) {
+        self.layer.use_text(text, size, Mm(x), Mm(self.y - size * PT_TO_MM), &self.font);
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, center: bool) {
+        let width = PAGE_WIDTH - MARGIN * 2.0;
+        for line in wrap(text, width, size) {
+            self.ensure_space(Self::line_height(size));
+            let x = if center {
+                MARGIN + ((width - text_width(&line, size)) / 2.0).max(0.0)
+            } else {
+                MARGIN
+            };
+            self.text_at(&line, size, x);
+            self.y -= Self::line_height(size);
+        }
+    }
+
+    fn table(&mut self, table: &Table, size: f32) {
+        let width = PAGE_WIDTH - MARGIN * 2.0;
+        let total: u32 = table.widths.iter().sum::<u32>().max(1);
+        let columns: Vec<f32> = (0..table.headers.len())
+            .map(|i| width * table.widths.get(i).copied().unwrap_or(1) as f32 / total as f32)
+            .collect();
+        let padding = 1.5;
+
+        for (index, cells) in std::iter::once(&table.headers).chain(&table.rows).enumerate() {
+            let wrapped: Vec<Vec<String>> = cells
+                .iter()
+                .zip(&columns)
+                .map(|(text, column)| wrap(text, column - padding * 2.0, size))
+                .collect();
+            let lines = wrapped.iter().map(|w| w.len()).max().unwrap_or(1);
+            self.ensure_space(Self::line_height(size) * lines as f32);
+
+            let top = self.y;
+            let mut x = MARGIN;
+            for (cell, column) in wrapped.iter().zip(&columns) {
+                self.y = top;
+                for line in cell {
+                    self.text_at(line, size, x + padding);
+                    self.y -= Self::line_height(size);
+                }
+                x += column;
+            }
+            self.y = top - Self::line_height(size) * lines as f32;
+            // 表头和内容之间留出间隔
+            if index == 0 {
+                self.y -= Self::line_height(size) * 0.3;
+            }
+        }
+    }
+
+    fn space(&mut self, size: f32) {
+        self.y -= Self::line_height(size) * 0.5;
+    }
+}
+
+/// 生成PDF文件内容，文字使用嵌入的中文字体
+pub fn build_pdf(doc: &ReportDocument, font_path: Option<&str>) -> Result<Vec<u8>, String> {
+    let font = load_cjk_font(font_path)?;
+    let mut writer = PdfWriter::new(&doc.title, &font)?;
+
+    writer.paragraph(&doc.title, 18.0, true);
+    if let Some(subtitle) = &doc.subtitle {
+        writer.paragraph(subtitle, 11.0, true);
+    }
+    writer.space(11.0);
+
+    for block in &doc.blocks {
+        match block {
+            Block::Heading(text) => {
+                writer.space(14.0);
+                writer.paragraph(text, 14.0, false);
+            }
+            Block::Paragraph(text) => writer.paragraph(text, 11.0, false),
+            Block::Table(table) => writer.table(table, 10.0),
+        }
+    }
+
+    writer.doc.save_to_bytes().map_err(|e| format!("生成PDF失败: {}", e))
+}
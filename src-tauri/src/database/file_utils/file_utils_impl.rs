@@ -44,7 +44,8 @@ pub fn select_file(app_handle: tauri::AppHandle, title: String, filters: Vec<ser
     }
 }
 
-pub fn select_save_path(app_handle: tauri::AppHandle, title: String, default_path: String, filters: Vec<serde_json::Value>) -> Result<String, String> {
+// 用户取消选择时返回 None
+pub fn select_save_path(app_handle: tauri::AppHandle, title: String, default_path: String, filters: Vec<serde_json::Value>) -> Result<Option<String>, String> {
     // 创建保存对话框
     let dialog = app_handle.dialog();
     let mut dialog_builder = dialog.file()
@@ -78,8 +79,7 @@ pub fn select_save_path(app_handle: tauri::AppHandle, title: String, default_pat
     
     // 等待结果
     match rx.recv() {
-        Ok(Some(path)) => Ok(path.to_string()),
-        Ok(None) => Err("用户取消选择".to_string()),
+        Ok(path) => Ok(path.map(|p| p.to_string())),
         Err(_) => Err("选择保存路径时发生错误".to_string())
     }
 }
@@ -13,6 +13,6 @@ pub fn select_file(app_handle: tauri::AppHandle, title: String, filters: Vec<ser
 }
 
 #[tauri::command]
-pub fn select_save_path(app_handle: tauri::AppHandle, title: String, default_path: String, filters: Vec<serde_json::Value>) -> Result<String, String> {
+pub fn select_save_path(app_handle: tauri::AppHandle, title: String, default_path: String, filters: Vec<serde_json::Value>) -> Result<Option<String>, String> {
     file_utils_impl::select_save_path(app_handle, title, default_path, filters)
 } 
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::process::Command;
 use std::path::Path;
+use serde::{Deserialize, Serialize};
 
 /// 获取指定仓库路径的Git未提交更改列表
 /// 
@@ -120,6 +121,72 @@ pub fn format_git_changes_for_daily(repo_path: &str, prefix: &str) -> Result<Str
     }
 }
 
+/// 单条Git提交记录
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author: String,
+    pub time: String, // ISO 8601格式的提交时间
+    pub message: String,
+}
+
+/// 获取指定时间之后的提交列表（按时间倒序）
+///
+/// # 参数
+/// * `repo_path` - Git仓库所在的路径
+/// * `since` - 起始时间，支持git可识别的任意格式，比如"2024-05-01"或"1 week ago"
+///
+/// # 返回值
+/// * `Result<Vec<GitCommit>, String>` - 成功时返回提交列表，失败时返回错误信息
+pub fn get_commits_since(repo_path: &str, since: &str) -> Result<Vec<GitCommit>, String> {
+    if !Path::new(repo_path).exists() {
+        return Err(format!("路径不存在: {}", repo_path));
+    }
+
+    // 使用不可见分隔符拆分字段，避免提交信息中的空格或竖线干扰解析
+    let git_log = Command::new("git")
+        .args(&[
+            "-C",
+            repo_path,
+            "log",
+            &format!("--since={}", since),
+            "--date=iso-strict",
+            "--pretty=format:%H%x1f%an%x1f%ad%x1f%B%x1e",
+        ])
+        .output();
+
+    match git_log {
+        Ok(output) => {
+            if !output.status.success() {
+                return Err(format!("获取Git日志失败, 退出码: {}", output.status));
+            }
+
+            let output_str = String::from_utf8_lossy(&output.stdout);
+            let commits = output_str
+                .split('\x1e')
+                .filter(|record| !record.trim().is_empty())
+                .filter_map(|record| {
+                    let fields: Vec<&str> = record.trim_start_matches('\n').splitn(4, '\x1f').collect();
+                    if fields.len() < 4 {
+                        return None;
+                    }
+                    Some(GitCommit {
+                        hash: fields[0].to_string(),
+                        author: fields[1].to_string(),
+                        time: fields[2].to_string(),
+                        message: fields[3].trim().to_string(),
+                    })
+                })
+                .collect();
+
+            Ok(commits)
+        },
+        Err(e) => {
+            Err(format!("执行Git命令失败: {}", e))
+        }
+    }
+}
+
 /// Tauri命令：获取Git未提交更改
 pub fn get_git_changes(repo_path: &str, prefix: &str) -> Result<String, String> {
     format_git_changes_for_daily(repo_path, prefix)
@@ -6,6 +6,13 @@ pub mod supabase;
 pub mod excel;
 pub mod file_utils;
 pub mod git_utils;
+pub mod tickets;
+pub mod work_log;
+pub mod scheduler;
+pub mod calendar;
+pub mod coverage;
+pub mod export;
+pub mod task_import;
 
 // Re-export types
 pub use types::*;
@@ -13,7 +20,11 @@ pub use types::*;
 // Re-export main functionality
 pub use sqlite::init_db;
 pub use sqlite::{save_daily_report_to_sqlite, fix_field_mapping};
-pub use report::{auto_generate_weekly, render_daily_with_template, save_template, read_template, start_weekly_timer};
+pub use report::{auto_generate_weekly, generate_weekly_report, render_report_html, render_daily_with_template, save_template, read_template,
+                 validate_template,
+                 list_templates, get_named_template, save_named_template, delete_named_template,
+                 list_template_partials, save_template_partial, delete_template_partial, preview_template,
+                 get_sample_context};
 pub use supabase::{
     save_daily_report, 
     get_recent_daily_reports, 
@@ -25,9 +36,18 @@ pub use supabase::{
     init_supabase
 };
 pub use excel::{parse_excel_template, generate_excel_report, save_report_template, load_report_template,
-               import_excel_tasks, generate_weekly_from_daily, export_with_template, generate_monthly_report};
+               import_excel_tasks, generate_weekly_from_daily, export_with_template, generate_monthly_report,
+               load_template_mapping, save_template_mapping, validate_template_mapping,
+               detect_excel_headers, get_header_synonyms, save_header_synonyms, list_excel_sheets, preview_excel_import};
 pub use file_utils::{read_text_file, select_file, select_save_path};
 pub use git_utils::{get_git_changes, get_git_last_commit};
+pub use tickets::{get_tracker_patterns, save_tracker_patterns, extract_ticket_refs, extract_tickets_from_git, tasks_by_ticket};
+pub use work_log::{get_watched_repos, save_watched_repos, scan_work_log_now, get_work_log, get_daily_timeline, start_work_log_watcher};
+pub use scheduler::{list_scheduled_jobs, save_scheduled_job, delete_scheduled_job, run_scheduled_job_now, list_upcoming_runs, start_scheduler};
+pub use calendar::{list_calendar_days, get_holiday_data, save_holiday_data, import_holiday_ics, list_personal_leave, add_personal_leave, remove_personal_leave};
+pub use coverage::{get_report_coverage, get_team_report_coverage};
+pub use export::{export_weekly_docx, export_monthly_docx, export_weekly_pdf, export_monthly_pdf};
+pub use task_import::{import_tasks_to_db, list_import_batches, undo_import_batch, list_planned_tasks};
 
 // Tauri commands re-exports
 #[tauri::command]
@@ -39,11 +59,15 @@ pub async fn save_daily(
     undone: String,
     user_id: Option<String>
 ) -> Result<String, String> {
+    // 从日报文本中识别任务编号，自动填充task_id
+    let task_id = tickets::TicketExtractor::from_config()
+        .task_id_for(&format!("{}\n{}\n{}\n{}", should, done, undone, content));
+
     let report = DailyReport {
         id: None,
         user_id,
         date,
-        task_id: None,
+        task_id,
         task_name: None,
         should_complete: should,
         completed: done,
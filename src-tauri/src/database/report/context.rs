@@ -0,0 +1,358 @@
+//! 模板渲染上下文
+//!
+//! 模板中可以使用的字段都定义在这里，字段调整时递增 `CONTEXT_VERSION`，
+//! 模板可以通过 `{{version}}` 判断上下文版本。
+//!
+//! 日报上下文（`DailyContext`）：
+//! - `date` `should` `done` `undone` `content`：与旧版模板兼容的原始文本
+//! - `report`：完整的日报记录（`task_id`、`plan_hours`、`actual_hours`、`remarks` 等）
+//! - `should_items` `done_items` `undone_items`：按行拆分后的任务列表
+//! - `tasks`：从日报解析出的任务（含编号、状态、工时），任务文本已去掉行首编号
+//! - `totals`：任务数与工时合计
+//! - `user`：用户信息（岗位、部门、姓名），未提供时为空
+//! - `dates`：星期、ISO周数、所在周起止、前后工作日
+//! - `commits`：当天的Git提交，未配置仓库时为空列表
+//!
+//! 周报上下文（`WeeklyContext`）在此基础上增加 `dailies`（每天的日报上下文）、
+//! 合并后的 `tasks`、`next_week_plan` 与 `next_week_items`，以及：
+//! - `done_tasks` `in_progress_tasks`：按周末状态拆分的任务
+//! - `carried_over_tasks`：上周未完成、本周继续跟进的任务（可能同时出现在前两个列表中）
+//! - `missing_workdays`：截至今天没有日报的工作日
+//!
+//! 月报上下文（`MonthlyContext`）包含 `year_month`、当月的 `dailies`、合并后的 `tasks`
+//! 及 `done_tasks` `in_progress_tasks`、`totals` 和 `user`。
+//!
+//! 版本记录：2 增加了周报的任务分组和 `missing_workdays`；3 增加了月报上下文
+
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::database::types::{DailyReport, Task, UserInfo};
+use crate::database::calendar::{month_range, week_range, WorkCalendar};
+use crate::database::git_utils::{get_commits_since, GitCommit};
+use crate::database::sqlite::latest_dailies_by_date;
+use crate::database::excel::utils::{extract_task_items, extract_tasks_from_daily};
+use super::template_manager::strip_marker;
+
+pub const CONTEXT_VERSION: u32 = 3;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const WEEKDAY_NAMES: [&str; 7] = ["星期一", "星期二", "星期三", "星期四", "星期五", "星期六", "星期日"];
+
+/// 构建上下文时的可选数据
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ContextOptions {
+    #[serde(default)]
+    pub user_info: Option<UserInfo>,
+    #[serde(default)]
+    pub repo_paths: Vec<String>, // 需要附带提交记录的Git仓库
+}
+
+/// 任务数与工时合计
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Totals {
+    pub task_count: usize,
+    pub done_count: usize,
+    pub undone_count: usize,
+    pub plan_hours: f64,
+    pub actual_hours: f64,
+}
+
+/// 日期相关信息
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DateContext {
+    pub weekday: String, // 星期一
+    pub iso_year: i32,
+    pub iso_week: u32,
+    pub week_start: String,
+    pub week_end: String,
+    pub yesterday: String,
+    pub tomorrow: String,
+    pub previous_workday: String,
+    pub next_workday: String,
+    pub is_workday: bool,
+}
+
+/// 日报模板上下文
+#[derive(Serialize, Clone)]
+pub struct DailyContext {
+    pub version: u32,
+    pub date: String,
+    pub should: String,
+    pub done: String,
+    pub undone: String,
+    pub content: String,
+    pub report: DailyReport,
+    pub should_items: Vec<String>,
+    pub done_items: Vec<String>,
+    pub undone_items: Vec<String>,
+    pub tasks: Vec<Task>,
+    pub totals: Totals,
+    pub user: Option<UserInfo>,
+    pub dates: Option<DateContext>,
+    pub commits: Vec<GitCommit>,
+}
+
+/// 周报模板上下文
+#[derive(Serialize, Clone)]
+pub struct WeeklyContext {
+    pub version: u32,
+    pub start_date: String,
+    pub end_date: String,
+    pub iso_year: i32,
+    pub iso_week: u32,
+    pub dailies: Vec<DailyContext>,
+    pub tasks: Vec<Task>, // 整周合并后的任务，同一任务只出现一次
+    pub done_tasks: Vec<Task>,
+    pub in_progress_tasks: Vec<Task>,
+    pub carried_over_tasks: Vec<Task>,
+    pub missing_workdays: Vec<String>,
+    pub totals: Totals,
+    pub next_week_plan: String,
+    pub next_week_items: Vec<String>,
+    pub user: Option<UserInfo>,
+    pub commits: Vec<GitCommit>,
+}
+
+/// 月报模板上下文
+#[derive(Serialize, Clone)]
+pub struct MonthlyContext {
+    pub version: u32,
+    pub year_month: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub dailies: Vec<DailyContext>,
+    pub tasks: Vec<Task>,
+    pub done_tasks: Vec<Task>,
+    pub in_progress_tasks: Vec<Task>,
+    pub totals: Totals,
+    pub user: Option<UserInfo>,
+}
+
+fn parse_hours(value: &Option<String>) -> f64 {
+    value
+        .as_deref()
+        .map(|v| v.trim().trim_end_matches(['h', 'H']).trim())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.0)
+}
+
+// 去掉每行的编号，避免同一任务在不同日报里编号不同而被当成两个任务
+fn normalize_report(report: &DailyReport) -> DailyReport {
+    let strip = |text: &str| {
+        text.lines()
+            .map(strip_marker)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    DailyReport {
+        should_complete: strip(&report.should_complete),
+        completed: strip(&report.completed),
+        uncompleted: strip(&report.uncompleted),
+        ..report.clone()
+    }
+}
+
+// 解析任务并按首次出现的日期排序，保证每次渲染的顺序一致
+fn parse_tasks(dailies: &[DailyReport]) -> Result<Vec<Task>, String> {
+    let normalized: Vec<DailyReport> = dailies.iter().map(normalize_report).collect();
+    let mut tasks = extract_tasks_from_daily(&normalized)?;
+    tasks.sort_by(|a, b| (&a.plan_start_time, &a.task).cmp(&(&b.plan_start_time, &b.task)));
+    Ok(tasks)
+}
+
+fn task_totals(tasks: &[Task]) -> Totals {
+    Totals {
+        task_count: tasks.len(),
+        done_count: tasks.iter().filter(|t| t.status == "已完成").count(),
+        undone_count: tasks.iter().filter(|t| t.status != "已完成").count(),
+        plan_hours: tasks.iter().map(|t| parse_hours(&t.plan_hours)).sum(),
+        actual_hours: tasks.iter().map(|t| parse_hours(&t.actual_hours)).sum(),
+    }
+}
+
+fn date_context(date: &str, calendar: &WorkCalendar) -> Option<DateContext> {
+    let day = NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?;
+    let (week_start, week_end) = week_range(day);
+    let format = |d: NaiveDate| d.format(DATE_FORMAT).to_string();
+    Some(DateContext {
+        weekday: WEEKDAY_NAMES[day.weekday().num_days_from_monday() as usize].to_string(),
+        iso_year: day.iso_week().year(),
+        iso_week: day.iso_week().week(),
+        week_start: format(week_start),
+        week_end: format(week_end),
+        yesterday: format(day - Duration::days(1)),
+        tomorrow: format(day + Duration::days(1)),
+        previous_workday: format(calendar.previous_workday(day)),
+        next_workday: format(calendar.next_workday(day)),
+        is_workday: calendar.is_workday(day),
+    })
+}
+
+// 收集多个仓库在日期范围内（含首尾）的提交，按时间正序
+fn collect_commits(repo_paths: &[String], start_date: &str, end_date: &str) -> Vec<GitCommit> {
+    let mut commits = Vec::new();
+    for repo in repo_paths {
+        match get_commits_since(repo, &format!("{} 00:00:00", start_date)) {
+            Ok(list) => commits.extend(list.into_iter().filter(|c| {
+                let day = c.time.get(0..10).unwrap_or_default();
+                day >= start_date && day <= end_date
+            })),
+            Err(e) => eprintln!("读取仓库 {} 的提交失败: {}", repo, e),
+        }
+    }
+    commits.sort_by(|a, b| a.time.cmp(&b.time));
+    commits
+}
+
+fn daily_context_with(
+    report: &DailyReport,
+    options: &ContextOptions,
+    calendar: &WorkCalendar,
+    commits: Vec<GitCommit>,
+) -> DailyContext {
+    let tasks = parse_tasks(std::slice::from_ref(report)).unwrap_or_default();
+    let mut totals = task_totals(&tasks);
+    // 日报级别的工时优先于按任务累加的结果
+    if report.plan_hours.is_some() {
+        totals.plan_hours = parse_hours(&report.plan_hours);
+    }
+    if report.actual_hours.is_some() {
+        totals.actual_hours = parse_hours(&report.actual_hours);
+    }
+
+    DailyContext {
+        version: CONTEXT_VERSION,
+        date: report.date.clone(),
+        should: report.should_complete.clone(),
+        done: report.completed.clone(),
+        undone: report.uncompleted.clone(),
+        content: report.remarks.clone(),
+        report: report.clone(),
+        should_items: extract_task_items(&report.should_complete),
+        done_items: extract_task_items(&report.completed),
+        undone_items: extract_task_items(&report.uncompleted),
+        tasks,
+        totals,
+        user: options.user_info.clone(),
+        dates: date_context(&report.date, calendar),
+        commits,
+    }
+}
+
+/// 构建单篇日报的模板上下文
+pub fn build_daily_context(report: &DailyReport, options: &ContextOptions) -> DailyContext {
+    let calendar = WorkCalendar::for_user(report.user_id.as_deref());
+    let commits = collect_commits(&options.repo_paths, &report.date, &report.date);
+    daily_context_with(report, options, &calendar, commits)
+}
+
+/// 构建周报的模板上下文
+///
+/// `dailies` 应为该范围内的日报，`previous_dailies` 为上一周的日报，用于找出遗留任务；
+/// 同一天保存了多份时只使用最后一份非空日报
+pub fn build_weekly_context(
+    start_date: &str,
+    end_date: &str,
+    dailies: &[DailyReport],
+    previous_dailies: &[DailyReport],
+    next_week_plan: Option<String>,
+    options: &ContextOptions,
+) -> Result<WeeklyContext, String> {
+    let dailies = latest_dailies_by_date(dailies.to_vec());
+    let previous_dailies = latest_dailies_by_date(previous_dailies.to_vec());
+    let start = NaiveDate::parse_from_str(start_date, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", start_date))?;
+    let end = NaiveDate::parse_from_str(end_date, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", end_date))?;
+    let calendar = WorkCalendar::for_user(dailies.first().and_then(|d| d.user_id.as_deref()));
+    let commits = collect_commits(&options.repo_paths, start_date, end_date);
+
+    let mut commits_by_day: HashMap<String, Vec<GitCommit>> = HashMap::new();
+    for commit in &commits {
+        let day = commit.time.get(0..10).unwrap_or_default().to_string();
+        commits_by_day.entry(day).or_default().push(commit.clone());
+    }
+    let daily_contexts: Vec<DailyContext> = dailies
+        .iter()
+        .map(|report| {
+            let day_commits = commits_by_day.get(&report.date).cloned().unwrap_or_default();
+            daily_context_with(report, options, &calendar, day_commits)
+        })
+        .collect();
+
+    let tasks = parse_tasks(&dailies)?;
+    let leftovers: Vec<String> = parse_tasks(&previous_dailies)?
+        .into_iter()
+        .filter(|t| t.status != "已完成")
+        .map(|t| t.task)
+        .collect();
+    let done_tasks = tasks.iter().filter(|t| t.status == "已完成").cloned().collect();
+    let in_progress_tasks = tasks.iter().filter(|t| t.status != "已完成").cloned().collect();
+    let carried_over_tasks = tasks.iter().filter(|t| leftovers.contains(&t.task)).cloned().collect();
+
+    let today = Local::now().date_naive();
+    let missing_workdays = calendar
+        .workdays_between(start, end.min(today))
+        .into_iter()
+        .map(|d| d.format(DATE_FORMAT).to_string())
+        .filter(|d| !dailies.iter().any(|r| &r.date == d))
+        .collect();
+
+    let mut totals = task_totals(&tasks);
+    // 工时按每天的日报累加，避免同一任务跨天时只计一次
+    totals.plan_hours = daily_contexts.iter().map(|d| d.totals.plan_hours).sum();
+    totals.actual_hours = daily_contexts.iter().map(|d| d.totals.actual_hours).sum();
+
+    let next_week_plan = next_week_plan.unwrap_or_default();
+    Ok(WeeklyContext {
+        version: CONTEXT_VERSION,
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        iso_year: start.iso_week().year(),
+        iso_week: start.iso_week().week(),
+        dailies: daily_contexts,
+        tasks,
+        done_tasks,
+        in_progress_tasks,
+        carried_over_tasks,
+        missing_workdays,
+        totals,
+        next_week_items: extract_task_items(&next_week_plan),
+        next_week_plan,
+        user: options.user_info.clone(),
+        commits,
+    })
+}
+
+/// 构建月报的模板上下文，`dailies` 应为该月的日报，同一天保存了多份时只使用最后一份非空日报
+pub fn build_monthly_context(
+    year_month: &str,
+    dailies: &[DailyReport],
+    options: &ContextOptions,
+) -> Result<MonthlyContext, String> {
+    let dailies = latest_dailies_by_date(dailies.to_vec());
+    let (start, end) = month_range(year_month)?;
+    let calendar = WorkCalendar::for_user(dailies.first().and_then(|d| d.user_id.as_deref()));
+    let daily_contexts: Vec<DailyContext> = dailies
+        .iter()
+        .map(|report| daily_context_with(report, options, &calendar, Vec::new()))
+        .collect();
+
+    let tasks = parse_tasks(&dailies)?;
+    let mut totals = task_totals(&tasks);
+    totals.plan_hours = daily_contexts.iter().map(|d| d.totals.plan_hours).sum();
+    totals.actual_hours = daily_contexts.iter().map(|d| d.totals.actual_hours).sum();
+
+    Ok(MonthlyContext {
+        version: CONTEXT_VERSION,
+        year_month: year_month.to_string(),
+        start_date: start.format(DATE_FORMAT).to_string(),
+        end_date: end.format(DATE_FORMAT).to_string(),
+        dailies: daily_contexts,
+        done_tasks: tasks.iter().filter(|t| t.status == "已完成").cloned().collect(),
+        in_progress_tasks: tasks.iter().filter(|t| t.status != "已完成").cloned().collect(),
+        tasks,
+        totals,
+        user: options.user_info.clone(),
+    })
+}
@@ -0,0 +1,75 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::database::calendar::month_range;
+use crate::database::sqlite::{init_db, load_latest_daily, load_latest_dailies_between};
+use super::context::{build_daily_context, build_monthly_context, ContextOptions};
+use super::report_impl::write_report_file;
+use super::template_manager::{TemplateManager, DEFAULT_TEMPLATE_NAME};
+use super::weekly::{build_weekly_report_context, WeeklyReportOptions};
+
+/// HTML报告的参数
+///
+/// 日报使用 `date`，月报使用 `year_month`，周报使用与 `generate_weekly_report` 相同的范围参数；
+/// 传入 `data` 时直接用它渲染，不再读取日报。`template_name` 和 `output_path` 指HTML模板和HTML文件
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct HtmlReportOptions {
+    pub data: Option<Value>,
+    pub date: Option<String>,
+    pub year_month: Option<String>,
+    #[serde(flatten)]
+    pub range: WeeklyReportOptions,
+}
+
+fn context_options(options: &HtmlReportOptions) -> ContextOptions {
+    ContextOptions {
+        user_info: options.range.user_info.clone(),
+        repo_paths: options.range.repo_paths.clone(),
+    }
+}
+
+fn build_data(kind: &str, options: &HtmlReportOptions) -> Result<Value, String> {
+    let user_id = options.range.user_id.as_deref();
+    match kind {
+        "daily" => {
+            let date = options.date.clone().unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+            let conn = init_db().map_err(|e| e.to_string())?;
+            let report = load_latest_daily(&conn, &date, user_id)?
+                .ok_or_else(|| format!("{} 没有日报", date))?;
+            serde_json::to_value(build_daily_context(&report, &context_options(options))).map_err(|e| e.to_string())
+        }
+        "weekly" => serde_json::to_value(build_weekly_report_context(&options.range)?).map_err(|e| e.to_string()),
+        "monthly" => {
+            let year_month = options.year_month.clone().unwrap_or_else(|| Local::now().format("%Y-%m").to_string());
+            let (start, end) = month_range(&year_month)?;
+            let conn = init_db().map_err(|e| e.to_string())?;
+            let dailies = load_latest_dailies_between(
+                &conn,
+                &start.format("%Y-%m-%d").to_string(),
+                &end.format("%Y-%m-%d").to_string(),
+                user_id,
+            )?;
+            let context = build_monthly_context(&year_month, &dailies, &context_options(options))?;
+            serde_json::to_value(context).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("不支持的报告类型: {}", kind)),
+    }
+}
+
+/// 使用 `<kind>_html` 模板渲染日报、周报或月报，返回完整的HTML文档
+///
+/// 提供 `output_path` 时同时保存为HTML文件，可直接打印或作为邮件正文
+pub fn render_report_html(kind: &str, options: &HtmlReportOptions) -> Result<String, String> {
+    let data = match &options.data {
+        Some(data) => data.clone(),
+        None => build_data(kind, options)?,
+    };
+    let template_name = options.range.template_name.as_deref().unwrap_or(DEFAULT_TEMPLATE_NAME);
+    let html = TemplateManager::new().render(&format!("{}_html", kind), template_name, &data)?;
+
+    if let Some(path) = &options.range.output_path {
+        write_report_file(path, &html)?;
+    }
+    Ok(html)
+}
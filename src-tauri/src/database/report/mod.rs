@@ -1,17 +1,45 @@
 // Report module
 mod report_impl;
+mod template_manager;
+mod context;
+mod weekly;
+mod validate;
+mod html;
 
 // Re-export with #[tauri::command] preservation
-pub use report_impl::start_weekly_timer;
+pub use report_impl::render_daily;
+pub use template_manager::{set_template_dir, TemplateInfo, TemplateManager};
+pub use weekly::{WeeklyReport, WeeklyReportOptions};
+pub use validate::TemplateValidation;
+pub use html::HtmlReportOptions;
 
 #[tauri::command]
 pub fn auto_generate_weekly() -> Result<(), String> {
     report_impl::auto_generate_weekly()
 }
 
+// 按ISO周或日期范围生成周报，未指定 output_path 时只返回内容
 #[tauri::command]
-pub fn save_template(content: String) -> Result<(), String> {
-    report_impl::save_template(content)
+pub fn generate_weekly_report(options: WeeklyReportOptions) -> Result<WeeklyReport, String> {
+    weekly::generate_weekly_report(&options)
+}
+
+// 使用HTML模板渲染日报、周报或月报，用于应用内预览、保存为网页或作为邮件正文
+#[tauri::command]
+pub fn render_report_html(kind: String, options: Option<HtmlReportOptions>) -> Result<String, String> {
+    html::render_report_html(&kind, &options.unwrap_or_default())
+}
+
+// 模板有错误时拒绝保存，force 为 true 时强制保存
+#[tauri::command]
+pub fn save_template(content: String, force: Option<bool>) -> Result<(), String> {
+    report_impl::save_template(content, force.unwrap_or(false))
+}
+
+// 检查模板语法以及变量、helper是否存在，kind 为空时按公共片段检查
+#[tauri::command]
+pub fn validate_template(kind: Option<String>, content: String) -> Result<TemplateValidation, String> {
+    validate::validate_template(kind.as_deref(), &content)
 }
 
 #[tauri::command]
@@ -24,7 +52,60 @@ pub fn render_daily_with_template(
     date: String,
     should: String,
     done: String,
-    undone: String
+    undone: String,
+    content: Option<String>,
+    user_info: Option<crate::database::types::UserInfo>,
+    repo_paths: Option<Vec<String>>,
 ) -> Result<String, String> {
-    report_impl::render_daily_with_template(date, should, done, undone)
-} 
\ No newline at end of file
+    report_impl::render_daily_with_template(date, should, done, undone, content, user_info, repo_paths)
+}
+
+#[tauri::command]
+pub fn list_templates(kind: Option<String>) -> Result<Vec<TemplateInfo>, String> {
+    TemplateManager::new().list(kind.as_deref())
+}
+
+#[tauri::command]
+pub fn get_named_template(kind: String, name: String) -> Result<String, String> {
+    TemplateManager::new().get(&kind, &name)
+}
+
+#[tauri::command]
+pub fn save_named_template(kind: String, name: String, content: String, force: Option<bool>) -> Result<(), String> {
+    validate::ensure_valid(Some(&kind), &content, force.unwrap_or(false))?;
+    TemplateManager::new().save(&kind, &name, &content)
+}
+
+#[tauri::command]
+pub fn delete_named_template(kind: String, name: String) -> Result<(), String> {
+    TemplateManager::new().delete(&kind, &name)
+}
+
+#[tauri::command]
+pub fn list_template_partials() -> Vec<TemplateInfo> {
+    TemplateManager::new().partials()
+}
+
+#[tauri::command]
+pub fn save_template_partial(name: String, content: String, force: Option<bool>) -> Result<(), String> {
+    validate::ensure_valid(None, &content, force.unwrap_or(false))?;
+    TemplateManager::new().save_partial(&name, &content)
+}
+
+#[tauri::command]
+pub fn delete_template_partial(name: String) -> Result<(), String> {
+    TemplateManager::new().delete_partial(&name)
+}
+
+// 使用示例数据（或传入的数据）渲染尚未保存的模板内容
+#[tauri::command]
+pub fn preview_template(kind: String, content: String, data: Option<serde_json::Value>) -> Result<String, String> {
+    let data = data.unwrap_or_else(|| template_manager::sample_data(&kind));
+    TemplateManager::new().render_content_as(&kind, &content, &data)
+}
+
+// 各类模板可用字段的示例数据
+#[tauri::command]
+pub fn get_sample_context(kind: String) -> serde_json::Value {
+    template_manager::sample_data(&kind)
+}
@@ -1,93 +1,76 @@
-use std::{thread, time::Duration};
-use handlebars::Handlebars;
-use chrono::{Datelike, Timelike};
+use chrono::{Duration, Local};
 use std::fs;
-use crate::database::sqlite::{init_db, save_daily_report_to_sqlite};
-use crate::database::types::DailyReport;
+use crate::database::sqlite::save_daily_report_to_sqlite;
+use crate::database::types::{DailyReport, UserInfo};
+use super::template_manager::{TemplateManager, DEFAULT_TEMPLATE_NAME};
+use super::context::{build_daily_context, ContextOptions};
+use super::weekly::{generate_weekly_report, WeeklyReportOptions};
+use super::validate::ensure_valid;
 
 pub fn auto_generate_weekly() -> Result<(), String> {
-    let conn = init_db().map_err(|e| e.to_string())?;
-    let mut stmt = conn.prepare(
-        "SELECT date, content, should, done, undone FROM dailies WHERE date >= date('now', '-7 days') ORDER BY date ASC, id ASC"
-    ).map_err(|e| e.to_string())?;
-
-    let template = std::fs::read_to_string("template.md").unwrap_or("{{date}}\n应完成：{{should}}\n已完成：{{done}}\n未完成：{{undone}}\n".to_string());
-    let mut handlebars = Handlebars::new();
-    handlebars
-        .register_template_string("tpl", template)
-        .map_err(|e| e.to_string())?;
-
-    let mut weekly = String::new();
-    let rows = stmt
-        .query_map([], |row| {
-            let date: String = row.get(0)?;
-            let _content: Option<String> = row.get(1)?;  // 备注
-            let should: Option<String> = row.get(2)?;   // 应完成
-            let done: Option<String> = row.get(3)?;     // 已完成
-            let undone: Option<String> = row.get(4)?;   // 未完成
-            Ok((
-                date,
-                should.unwrap_or_default(),
-                done.unwrap_or_default(),
-                undone.unwrap_or_default()
-            ))
-        })
-        .map_err(|e| e.to_string())?;
-
-    for row in rows {
-        let (date, should, done, undone) = row.map_err(|e| e.to_string())?;
-        let data = serde_json::json!({
-            "date": date,
-            "should": should,
-            "done": done,
-            "undone": undone
-        });
-        let rendered = handlebars.render("tpl", &data).map_err(|e| e.to_string())?;
-        weekly.push_str(&rendered);
-        weekly.push_str("\n\n");
-    }
-
-    std::fs::write("weekly.md", weekly).map_err(|e| e.to_string())?;
+    // 汇总包含昨天的自然周，周日凌晨执行时即为刚结束的一周；钉钉页面会读取 weekly.md
+    let yesterday = Local::now().date_naive() - Duration::days(1);
+    let options = WeeklyReportOptions {
+        start_date: Some(yesterday.format("%Y-%m-%d").to_string()),
+        output_path: Some("weekly.md".to_string()),
+        ..Default::default()
+    };
+    generate_weekly_report(&options)?;
     Ok(())
 }
 
-pub fn start_weekly_timer() {
-    thread::spawn(|| {
-        loop {
-            // 每周日凌晨1点自动生成周报
-            let now = chrono::Local::now();
-            if now.weekday() == chrono::Weekday::Sun && now.hour() == 1 && now.minute() == 0 {
-                let _ = auto_generate_weekly();
-            }
-            thread::sleep(Duration::from_secs(60)); // 每分钟检查一次
-        }
-    });
+/// 把生成的报告写入指定路径，目录不存在时自动创建
+pub fn write_report_file(path: &str, content: &str) -> Result<(), String> {
+    if let Some(parent) = std::path::Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败: {}", e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("写入报告失败: {}", e))
 }
 
-pub fn save_template(content: String) -> Result<(), String> {
-    std::fs::write("template.md", content).map_err(|e| e.to_string())
+// 默认日报模板，兼容只支持单个模板时的接口
+pub fn save_template(content: String, force: bool) -> Result<(), String> {
+    ensure_valid(Some("daily"), &content, force)?;
+    TemplateManager::new().save("daily", DEFAULT_TEMPLATE_NAME, &content)
 }
 
 pub fn read_template() -> Result<String, String> {
-    std::fs::read_to_string("template.md").map_err(|e| e.to_string())
+    TemplateManager::new().get("daily", DEFAULT_TEMPLATE_NAME)
+}
+
+/// 使用用户模板渲染一篇日报，模板不存在时使用默认格式
+pub fn render_daily(report: &DailyReport) -> Result<String, String> {
+    let context = build_daily_context(report, &ContextOptions::default());
+    let data = serde_json::to_value(&context).map_err(|e| e.to_string())?;
+    TemplateManager::new().render("daily", DEFAULT_TEMPLATE_NAME, &data)
 }
 
 pub fn render_daily_with_template(
     date: String,
     should: String,
     done: String,
-    undone: String
+    undone: String,
+    content: Option<String>,
+    user_info: Option<UserInfo>,
+    repo_paths: Option<Vec<String>>,
 ) -> Result<String, String> {
-    let template = std::fs::read_to_string("template.md").map_err(|e| e.to_string())?;
-    let mut handlebars = Handlebars::new();
-    handlebars
-        .register_template_string("tpl", template)
-        .map_err(|e| e.to_string())?;
-    let data = serde_json::json!({
-        "date": date,
-        "should": should,
-        "done": done,
-        "undone": undone
-    });
-    handlebars.render("tpl", &data).map_err(|e| e.to_string())
+    let report = DailyReport {
+        id: None,
+        user_id: None,
+        date,
+        task_id: None,
+        task_name: None,
+        should_complete: should,
+        completed: done,
+        uncompleted: undone,
+        plan_hours: None,
+        actual_hours: None,
+        remarks: content.unwrap_or_default(),
+    };
+    let options = ContextOptions {
+        user_info,
+        repo_paths: repo_paths.unwrap_or_default(),
+    };
+    let context = build_daily_context(&report, &options);
+    let data = serde_json::to_value(&context).map_err(|e| e.to_string())?;
+    TemplateManager::new().render("daily", DEFAULT_TEMPLATE_NAME, &data)
 }
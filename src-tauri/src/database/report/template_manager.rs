@@ -0,0 +1,521 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use chrono::{Datelike, NaiveDate};
+use handlebars::{handlebars_helper, html_escape, no_escape, Handlebars};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::database::types::{DailyReport, UserInfo};
+use super::context::{build_daily_context, build_monthly_context, build_weekly_context, ContextOptions};
+
+// 启动时由桌面端设置为应用数据目录下的 templates，未设置时使用工作目录下的 templates
+static TEMPLATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+const PARTIALS_DIR: &str = "partials";
+const TEMPLATE_EXT: &str = "hbs";
+// 旧版本只支持这一个日报模板
+const LEGACY_DAILY_TEMPLATE: &str = "template.md";
+
+/// 模板类型，每种类型下可以保存多个命名模板；`_html` 结尾的类型输出HTML，变量会做HTML转义
+pub const TEMPLATE_KINDS: [&str; 7] = ["daily", "weekly", "monthly", "dingtalk", "daily_html", "weekly_html", "monthly_html"];
+pub const DEFAULT_TEMPLATE_NAME: &str = "default";
+
+const DEFAULT_DAILY: &str = "{{date}}\n应完成：{{should}}\n已完成：{{done}}\n未完成：{{undone}}\n";
+const DEFAULT_WEEKLY: &str = "# 周报 {{start_date}} ~ {{end_date}}（{{iso_year}}年第{{iso_week}}周）\n\n\
+## 本周完成\n{{#each done_tasks}}- {{task}}\n{{else}}无\n{{/each}}\n\
+## 进行中\n{{#each in_progress_tasks}}- {{task}}\n{{else}}无\n{{/each}}\n\
+{{#if carried_over_tasks}}## 上周遗留\n{{#each carried_over_tasks}}- {{task}}（{{status}}）\n{{/each}}\n{{/if}}\
+## 下周计划\n{{#if next_week_plan}}{{numbered next_week_plan}}{{else}}{{#each in_progress_tasks}}- {{task}}\n{{/each}}{{/if}}\n\
+{{#if missing_workdays}}\n未填写日报的工作日：{{#each missing_workdays}}{{this}}{{#unless @last}}、{{/unless}}{{/each}}\n{{/if}}";
+const DEFAULT_MONTHLY: &str = "# {{year_month}} 月报\n\n{{#each dailies}}- {{date}}：{{done}}\n{{/each}}";
+const DEFAULT_DINGTALK: &str = "### {{format_date date \"%Y年%m月%d日\"}} {{weekday_cn date}} 日报\n\n**已完成**\n\n{{bullets done}}\n\n**未完成**\n\n{{bullets undone}}\n";
+const DEFAULT_DAILY_BODY_PARTIAL: &str = "已完成：\n{{numbered done}}\n未完成：\n{{numbered undone}}\n";
+// HTML报告共用的样式，兼顾屏幕预览、打印和邮件正文
+const DEFAULT_REPORT_STYLE_PARTIAL: &str = r#"<style>
+body { font-family: "PingFang SC", "Microsoft YaHei", "Noto Sans CJK SC", sans-serif; color: #222; margin: 0; background: #f5f6f8; }
+.report { max-width: 800px; margin: 24px auto; padding: 32px 40px; background: #fff; box-shadow: 0 1px 4px rgba(0, 0, 0, 0.08); }
+h1 { font-size: 22px; margin: 0 0 4px; }
+h2 { font-size: 16px; margin: 24px 0 8px; padding-left: 8px; border-left: 4px solid #2f6feb; }
+.meta { color: #666; margin: 0 0 16px; }
+.summary span { display: inline-block; margin-right: 16px; color: #444; }
+table { width: 100%; border-collapse: collapse; font-size: 14px; }
+th, td { border: 1px solid #d0d7de; padding: 6px 8px; text-align: left; vertical-align: top; white-space: pre-line; }
+th { background: #eef2f8; }
+ul.plain { list-style: none; padding-left: 0; }
+.empty { color: #999; }
+@page { size: A4; margin: 15mm; }
+@media print {
+  body { background: #fff; }
+  .report { max-width: none; margin: 0; padding: 0; box-shadow: none; }
+  h2, tr { page-break-inside: avoid; }
+  thead { display: table-header-group; }
+}
+</style>"#;
+const DEFAULT_DAILY_HTML: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head><meta charset="utf-8"><title>{{date}} 日报</title>{{> report_style}}</head>
+<body>
+<article class="report">
+<h1>日报</h1>
+<p class="meta">{{format_date date "%Y年%m月%d日"}} {{weekday_cn date}}{{#if user}} · {{user.department}} {{user.name}}{{/if}}</p>
+<h2>已完成</h2>
+<ul class="plain">{{#each done_items}}<li>{{this}}</li>{{else}}<li class="empty">无</li>{{/each}}</ul>
+<h2>未完成</h2>
+<ul class="plain">{{#each undone_items}}<li>{{this}}</li>{{else}}<li class="empty">无</li>{{/each}}</ul>
+<h2>计划</h2>
+<ul class="plain">{{#each should_items}}<li>{{this}}</li>{{else}}<li class="empty">无</li>{{/each}}</ul>
+{{#if content}}<h2>备注</h2>
+<p>{{content}}</p>{{/if}}
+</article>
+</body>
+</html>
+"#;
+const DEFAULT_WEEKLY_HTML: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head><meta charset="utf-8"><title>周报 {{start_date}} ~ {{end_date}}</title>{{> report_style}}</head>
+<body>
+<article class="report">
+<h1>周报</h1>
+<p class="meta">{{start_date}} ~ {{end_date}}（{{iso_year}}年第{{iso_week}}周）{{#if user}} · {{user.department}} {{user.name}}{{/if}}</p>
+<p class="summary"><span>任务 {{totals.task_count}}</span><span>已完成 {{totals.done_count}}</span><span>进行中 {{totals.undone_count}}</span><span>实际工时 {{totals.actual_hours}}</span></p>
+<h2>本周工作</h2>
+<table>
+<thead><tr><th>任务编号</th><th>任务内容</th><th>状态</th><th>实际工时</th></tr></thead>
+<tbody>{{#each tasks}}<tr><td>{{task_id}}</td><td>{{task}}</td><td>{{status}}</td><td>{{actual_hours}}</td></tr>{{else}}<tr><td colspan="4" class="empty">本周没有日报</td></tr>{{/each}}</tbody>
+</table>
+{{#if carried_over_tasks}}<h2>上周遗留</h2>
+<ul>{{#each carried_over_tasks}}<li>{{task}}（{{status}}）</li>{{/each}}</ul>{{/if}}
+<h2>下周计划</h2>
+<ul class="plain">{{#each next_week_items}}<li>{{this}}</li>{{else}}{{#each in_progress_tasks}}<li>{{task}}</li>{{else}}<li class="empty">无</li>{{/each}}{{/each}}</ul>
+{{#if missing_workdays}}<p class="meta">未填写日报的工作日：{{#each missing_workdays}}{{this}}{{#unless @last}}、{{/unless}}{{/each}}</p>{{/if}}
+</article>
+</body>
+</html>
+"#;
+const DEFAULT_MONTHLY_HTML: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head><meta charset="utf-8"><title>{{year_month}} 月报</title>{{> report_style}}</head>
+<body>
+<article class="report">
+<h1>{{year_month}} 月报</h1>
+<p class="meta">{{#if user}}{{user.department}} {{user.position}} {{user.name}} · {{/if}}已完成 {{totals.done_count}} 项，进行中 {{totals.undone_count}} 项</p>
+<h2>每日工作</h2>
+<table>
+<thead><tr><th>日期</th><th>已完成</th><th>未完成</th></tr></thead>
+<tbody>{{#each dailies}}<tr><td>{{format_date date "%m-%d"}} {{weekday_cn date}}</td><td>{{done}}</td><td>{{undone}}</td></tr>{{else}}<tr><td colspan="3" class="empty">本月没有日报</td></tr>{{/each}}</tbody>
+</table>
+<h2>下月计划</h2>
+<ul>{{#each in_progress_tasks}}<li>{{task}}</li>{{else}}<li class="empty">无</li>{{/each}}</ul>
+</article>
+</body>
+</html>
+"#;
+// 内置的公共片段，同名文件存在时以文件为准
+const BUILTIN_PARTIALS: [(&str, &str); 2] = [
+    ("daily_body", DEFAULT_DAILY_BODY_PARTIAL),
+    ("report_style", DEFAULT_REPORT_STYLE_PARTIAL),
+];
+
+/// 模板信息
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateInfo {
+    pub kind: String,
+    pub name: String,
+    pub content: String,
+}
+
+/// 设置模板目录，只有第一次调用生效
+pub fn set_template_dir(dir: PathBuf) {
+    let _ = TEMPLATE_DIR.set(dir);
+}
+
+fn template_dir() -> PathBuf {
+    TEMPLATE_DIR.get().cloned().unwrap_or_else(|| PathBuf::from("templates"))
+}
+
+// 模板名和类型都会作为文件名使用，不允许路径分隔符
+fn check_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("模板名称无效: {}", name));
+    }
+    Ok(())
+}
+
+fn check_kind(kind: &str) -> Result<(), String> {
+    if TEMPLATE_KINDS.contains(&kind) {
+        Ok(())
+    } else {
+        Err(format!("不支持的模板类型: {}", kind))
+    }
+}
+
+fn builtin_template(kind: &str) -> &'static str {
+    match kind {
+        "weekly" => DEFAULT_WEEKLY,
+        "monthly" => DEFAULT_MONTHLY,
+        "dingtalk" => DEFAULT_DINGTALK,
+        "daily_html" => DEFAULT_DAILY_HTML,
+        "weekly_html" => DEFAULT_WEEKLY_HTML,
+        "monthly_html" => DEFAULT_MONTHLY_HTML,
+        _ => DEFAULT_DAILY,
+    }
+}
+
+/// 输出HTML的模板类型
+pub fn is_html_kind(kind: &str) -> bool {
+    kind.ends_with("_html")
+}
+
+// 把日期字符串（YYYY-MM-DD 或带时间的 RFC 3339）解析为日期
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(0..10)?, "%Y-%m-%d").ok()
+}
+
+// 把数组或多行文本拆成非空的行
+fn to_lines(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => s.trim().to_string(),
+                other => other.to_string(),
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Value::String(text) => text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect(),
+        Value::Null => Vec::new(),
+        other => vec![other.to_string()],
+    }
+}
+
+/// 去掉行首已有的编号或列表符号，比如 "1. "、"2、"、"3）"、"- "
+///
+/// 只去掉一层标记，"1. 2025年规划" 中的年份不受影响
+pub(super) fn strip_marker(line: &str) -> &str {
+    let line = line.trim_start();
+    if let Some(rest) = line.strip_prefix(['-', '*', '•']) {
+        return rest.trim_start();
+    }
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() < line.len() {
+        if let Some(after) = rest.strip_prefix(['.', '、', ')', '）']) {
+            // "1.5小时" 这类小数不是编号
+            if !after.starts_with(|c: char| c.is_ascii_digit()) {
+                return after.trim_start();
+            }
+        }
+    }
+    line
+}
+
+fn to_hours(value: &Value) -> f64 {
+    match value {
+        Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        Value::String(s) => s.trim().trim_end_matches(['h', 'H']).trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+// {{format_date date "%Y年%m月%d日"}}，格式无效（如 "%Q"）时原样输出日期
+handlebars_helper!(format_date: |date: str, format: str| {
+    let mut out = String::new();
+    match parse_date(date) {
+        Some(d) if write!(out, "{}", d.format(format)).is_ok() => out,
+        _ => date.to_string(),
+    }
+});
+
+// {{weekday_cn date}} => 星期一
+handlebars_helper!(weekday_cn: |date: str| {
+    const NAMES: [&str; 7] = ["星期一", "星期二", "星期三", "星期四", "星期五", "星期六", "星期日"];
+    parse_date(date)
+        .map(|d| NAMES[d.weekday().num_days_from_monday() as usize].to_string())
+        .unwrap_or_default()
+});
+
+// {{numbered done}} => 1. xxx\n2. yyy，已有编号的行会重新编号
+handlebars_helper!(numbered: |items: Json| {
+    to_lines(items)
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{}. {}", i + 1, strip_marker(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+});
+
+// {{bullets done}} 或 {{bullets done prefix="* "}}
+handlebars_helper!(bullets: |text: Json, {prefix: str = "- "}| {
+    to_lines(text)
+        .iter()
+        .map(|line| format!("{}{}", prefix, strip_marker(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+});
+
+// {{sum_hours tasks}} 累加 actual_hours，{{sum_hours tasks field="plan_hours"}} 指定字段；也可以直接传数字数组
+handlebars_helper!(sum_hours: |items: Json, {field: str = "actual_hours"}| {
+    let total: f64 = match items {
+        Value::Array(list) => list
+            .iter()
+            .map(|item| match item {
+                Value::Object(map) => map.get(field).map(to_hours).unwrap_or(0.0),
+                other => to_hours(other),
+            })
+            .sum(),
+        other => to_hours(other),
+    };
+    // 整数不显示小数位
+    if total.fract() == 0.0 { format!("{}", total as i64) } else { format!("{:.1}", total) }
+});
+
+/// 自定义helper的名称，与 `register_helpers` 保持一致
+pub const CUSTOM_HELPERS: [&str; 5] = ["format_date", "weekday_cn", "numbered", "bullets", "sum_hours"];
+
+/// 注册全部自定义helper，Markdown输出不做HTML转义
+pub fn register_helpers(handlebars: &mut Handlebars) {
+    handlebars.register_escape_fn(no_escape);
+    handlebars.register_helper("format_date", Box::new(format_date));
+    handlebars.register_helper("weekday_cn", Box::new(weekday_cn));
+    handlebars.register_helper("numbered", Box::new(numbered));
+    handlebars.register_helper("bullets", Box::new(bullets));
+    handlebars.register_helper("sum_hours", Box::new(sum_hours));
+}
+
+/// 命名模板与公共片段的存储和渲染
+///
+/// 目录结构：`<dir>/<kind>/<name>.hbs`，公共片段在 `<dir>/partials/<name>.hbs`
+pub struct TemplateManager {
+    dir: PathBuf,
+}
+
+impl Default for TemplateManager {
+    fn default() -> Self {
+        TemplateManager { dir: template_dir() }
+    }
+}
+
+impl TemplateManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn template_path(&self, kind: &str, name: &str) -> PathBuf {
+        self.dir.join(kind).join(format!("{}.{}", name, TEMPLATE_EXT))
+    }
+
+    fn partial_path(&self, name: &str) -> PathBuf {
+        self.dir.join(PARTIALS_DIR).join(format!("{}.{}", name, TEMPLATE_EXT))
+    }
+
+    fn list_dir(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == TEMPLATE_EXT))
+                    .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// 读取模板内容，默认模板不存在时返回内置模板（日报会沿用旧的 template.md）
+    pub fn get(&self, kind: &str, name: &str) -> Result<String, String> {
+        check_kind(kind)?;
+        check_name(name)?;
+        match fs::read_to_string(self.template_path(kind, name)) {
+            Ok(content) => Ok(content),
+            Err(_) if name == DEFAULT_TEMPLATE_NAME => {
+                if kind == "daily" {
+                    if let Ok(content) = fs::read_to_string(LEGACY_DAILY_TEMPLATE) {
+                        return Ok(content);
+                    }
+                }
+                Ok(builtin_template(kind).to_string())
+            }
+            Err(_) => Err(format!("找不到模板: {}/{}", kind, name)),
+        }
+    }
+
+    pub fn list(&self, kind: Option<&str>) -> Result<Vec<TemplateInfo>, String> {
+        let kinds: Vec<&str> = match kind {
+            Some(kind) => {
+                check_kind(kind)?;
+                vec![kind]
+            }
+            None => TEMPLATE_KINDS.to_vec(),
+        };
+
+        let mut templates = Vec::new();
+        for kind in kinds {
+            let mut names = Self::list_dir(&self.dir.join(kind));
+            if !names.iter().any(|n| n == DEFAULT_TEMPLATE_NAME) {
+                names.insert(0, DEFAULT_TEMPLATE_NAME.to_string());
+            }
+            for name in names {
+                let content = self.get(kind, &name)?;
+                templates.push(TemplateInfo { kind: kind.to_string(), name, content });
+            }
+        }
+        Ok(templates)
+    }
+
+    pub fn save(&self, kind: &str, name: &str, content: &str) -> Result<(), String> {
+        check_kind(kind)?;
+        check_name(name)?;
+        let path = self.template_path(kind, name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建模板目录失败: {}", e))?;
+        }
+        fs::write(path, content).map_err(|e| format!("保存模板失败: {}", e))
+    }
+
+    pub fn delete(&self, kind: &str, name: &str) -> Result<(), String> {
+        check_kind(kind)?;
+        check_name(name)?;
+        fs::remove_file(self.template_path(kind, name)).map_err(|e| format!("删除模板失败: {}", e))
+    }
+
+    /// 所有公共片段，内置片段未被覆盖时也包含在内
+    pub fn partials(&self) -> Vec<TemplateInfo> {
+        let mut names = Self::list_dir(&self.dir.join(PARTIALS_DIR));
+        for (i, (name, _)) in BUILTIN_PARTIALS.iter().enumerate() {
+            if !names.iter().any(|n| n == name) {
+                names.insert(i, name.to_string());
+            }
+        }
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let content = fs::read_to_string(self.partial_path(&name)).ok().or_else(|| {
+                    BUILTIN_PARTIALS
+                        .iter()
+                        .find(|(builtin, _)| *builtin == name)
+                        .map(|(_, content)| content.to_string())
+                })?;
+                Some(TemplateInfo { kind: PARTIALS_DIR.to_string(), name, content })
+            })
+            .collect()
+    }
+
+    pub fn save_partial(&self, name: &str, content: &str) -> Result<(), String> {
+        check_name(name)?;
+        let path = self.partial_path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建模板目录失败: {}", e))?;
+        }
+        fs::write(path, content).map_err(|e| format!("保存公共片段失败: {}", e))
+    }
+
+    pub fn delete_partial(&self, name: &str) -> Result<(), String> {
+        check_name(name)?;
+        fs::remove_file(self.partial_path(name)).map_err(|e| format!("删除公共片段失败: {}", e))
+    }
+
+    /// 注册了helper和全部公共片段的模板引擎
+    pub fn registry(&self) -> Result<Handlebars<'static>, String> {
+        let mut handlebars = Handlebars::new();
+        register_helpers(&mut handlebars);
+        for partial in self.partials() {
+            handlebars
+                .register_partial(&partial.name, partial.content)
+                .map_err(|e| format!("公共片段 {} 有误: {}", partial.name, e))?;
+        }
+        Ok(handlebars)
+    }
+
+    fn render_with(&self, mut handlebars: Handlebars<'static>, content: &str, data: &Value) -> Result<String, String> {
+        handlebars
+            .register_template_string("tpl", content)
+            .map_err(|e| format!("模板有误: {}", e))?;
+        handlebars.render("tpl", data).map_err(|e| format!("渲染模板失败: {}", e))
+    }
+
+    /// 使用模板内容渲染（不需要先保存），供预览和渲染共用
+    pub fn render_content(&self, content: &str, data: &Value) -> Result<String, String> {
+        self.render_with(self.registry()?, content, data)
+    }
+
+    /// 按模板类型渲染内容，HTML类型会转义变量中的特殊字符
+    pub fn render_content_as(&self, kind: &str, content: &str, data: &Value) -> Result<String, String> {
+        let mut handlebars = self.registry()?;
+        if is_html_kind(kind) {
+            handlebars.register_escape_fn(html_escape);
+        }
+        self.render_with(handlebars, content, data)
+    }
+
+    pub fn render(&self, kind: &str, name: &str, data: &Value) -> Result<String, String> {
+        let content = self.get(kind, name)?;
+        self.render_content_as(kind, &content, data)
+    }
+}
+
+fn sample_report(date: &str, should: &str, done: &str, undone: &str, remarks: &str) -> DailyReport {
+    DailyReport {
+        id: None,
+        user_id: None,
+        date: date.to_string(),
+        task_id: None,
+        task_name: None,
+        should_complete: should.to_string(),
+        completed: done.to_string(),
+        uncompleted: undone.to_string(),
+        plan_hours: Some("8".to_string()),
+        actual_hours: Some("7.5".to_string()),
+        remarks: remarks.to_string(),
+    }
+}
+
+/// 各类模板的示例数据，用于预览
+///
+/// 与实际渲染使用同一套上下文构建逻辑，字段始终与 `context` 模块保持一致
+pub fn sample_data(kind: &str) -> Value {
+    let options = ContextOptions {
+        user_info: Some(UserInfo {
+            position: "前端工程师".to_string(),
+            department: "研发部".to_string(),
+            name: "张三".to_string(),
+            date: "2025-06".to_string(),
+        }),
+        repo_paths: Vec::new(),
+    };
+    let dailies = vec![
+        sample_report(
+            "2025-06-09",
+            "1. 完成登录页改版\n2. 修复导出Excel的编码问题",
+            "1. 完成登录页改版\n2. 评审PROJ-123接口设计",
+            "修复导出Excel的编码问题",
+            "下午参加需求评审会",
+        ),
+        sample_report("2025-06-10", "修复导出Excel的编码问题", "修复导出Excel的编码问题", "", ""),
+    ];
+
+    let value = match kind.trim_end_matches("_html") {
+        "weekly" => build_weekly_context(
+            "2025-06-09",
+            "2025-06-15",
+            &dailies,
+            &[sample_report("2025-06-06", "修复导出Excel的编码问题", "", "修复导出Excel的编码问题", "")],
+            Some("1. 上线登录页改版\n2. 开始报表模块开发".to_string()),
+            &options,
+        )
+        .and_then(|context| serde_json::to_value(context).map_err(|e| e.to_string())),
+        "monthly" => build_monthly_context("2025-06", &dailies, &options)
+            .and_then(|context| serde_json::to_value(context).map_err(|e| e.to_string())),
+        _ => serde_json::to_value(build_daily_context(&dailies[0], &options)).map_err(|e| e.to_string()),
+    };
+    value.unwrap_or_else(|e| {
+        eprintln!("生成示例数据失败: {}", e);
+        Value::Null
+    })
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+use handlebars::template::{BlockParam, HelperTemplate, Parameter, Template, TemplateElement};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use super::template_manager::{sample_data, TemplateManager, CUSTOM_HELPERS, TEMPLATE_KINDS};
+
+// handlebars 自带的helper
+const BUILTIN_HELPERS: [&str; 17] = [
+    "if", "unless", "each", "with", "lookup", "raw", "log",
+    "eq", "ne", "gt", "gte", "lt", "lte", "and", "or", "not", "len",
+];
+// @index、@key 等由 each 提供的局部变量
+const LOCAL_VARIABLES: [&str; 4] = ["index", "key", "first", "last"];
+
+/// 模板检查发现的问题
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateIssue {
+    pub severity: String, // error：无法渲染；warning：可以渲染但结果可能不符合预期
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+/// 模板检查结果，存在 error 时 `valid` 为 false
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateValidation {
+    pub valid: bool,
+    pub issues: Vec<TemplateIssue>,
+}
+
+// 当前作用域的数据，None 表示无法从示例数据推断（例如空数组），此时不检查变量
+type Scope = Option<Value>;
+
+struct Checker {
+    helpers: HashSet<String>,
+    partials: HashSet<String>,
+    issues: Vec<TemplateIssue>,
+}
+
+fn issue(severity: &str, message: String, pos: Option<(usize, usize)>) -> TemplateIssue {
+    TemplateIssue {
+        severity: severity.to_string(),
+        message,
+        line: pos.map(|p| p.0),
+        column: pos.map(|p| p.1),
+    }
+}
+
+// 把 "a.b/c"、"[a b].c" 拆成路径段
+fn split_path(path: &str) -> Vec<String> {
+    path.split(['.', '/'])
+        .filter(|seg| !seg.is_empty())
+        .map(|seg| seg.trim_start_matches('[').trim_end_matches(']').to_string())
+        .collect()
+}
+
+fn block_param_names(block_param: &Option<BlockParam>) -> Vec<String> {
+    let names = match block_param {
+        Some(BlockParam::Single(p)) => vec![p],
+        Some(BlockParam::Pair((a, b))) => vec![a, b],
+        None => Vec::new(),
+    };
+    names.into_iter().filter_map(|p| p.as_name()).map(|n| n.to_string()).collect()
+}
+
+// each 进入数组或对象后，块内的 this 对应第一项
+fn first_item(value: &Scope) -> Scope {
+    match value {
+        Some(Value::Array(items)) => items.first().cloned(),
+        Some(Value::Object(map)) => map.values().next().cloned(),
+        _ => None,
+    }
+}
+
+impl Checker {
+    fn is_helper(&self, name: &str) -> bool {
+        self.helpers.contains(name)
+    }
+
+    /// 按示例数据解析变量路径，找不到时记录 warning
+    fn resolve(
+        &mut self,
+        raw: &str,
+        scopes: &[Scope],
+        locals: &HashMap<String, Scope>,
+        pos: Option<(usize, usize)>,
+    ) -> Scope {
+        let mut rest = raw.trim();
+        let mut level = 0;
+        while let Some(r) = rest.strip_prefix("../") {
+            level += 1;
+            rest = r;
+        }
+
+        let mut segments = split_path(rest);
+        let mut value = if segments.first().is_some_and(|s| s == "@root") {
+            segments.remove(0);
+            scopes.first().cloned().flatten()
+        } else if let Some(first) = segments.first().and_then(|s| s.strip_prefix('@')) {
+            // @index 等局部变量不检查，其它以@开头的名称多半是拼写错误
+            if !LOCAL_VARIABLES.contains(&first) {
+                self.issues.push(issue("warning", format!("未知的局部变量: {}", raw), pos));
+            }
+            return None;
+        } else if let Some(local) = segments.first().and_then(|s| locals.get(s)) {
+            let local = local.clone();
+            segments.remove(0);
+            local
+        } else {
+            scopes.iter().rev().nth(level).cloned().flatten()
+        };
+        if segments.first().is_some_and(|s| s == "this") {
+            segments.remove(0);
+        }
+
+        for segment in &segments {
+            value = match value {
+                Some(Value::Object(ref map)) => match map.get(segment) {
+                    Some(v) => Some(v.clone()),
+                    None => {
+                        self.issues.push(issue("warning", format!("上下文中没有变量: {}", raw), pos));
+                        return None;
+                    }
+                },
+                Some(Value::Array(ref items)) => segment.parse::<usize>().ok().and_then(|i| items.get(i).cloned()),
+                // 示例数据为空的可选字段，无法继续推断
+                Some(Value::Null) | None => return None,
+                Some(_) => {
+                    self.issues.push(issue("warning", format!("{} 不是对象，无法读取其中的字段", raw), pos));
+                    return None;
+                }
+            };
+        }
+        value
+    }
+
+    fn check_param(
+        &mut self,
+        param: &Parameter,
+        scopes: &[Scope],
+        locals: &HashMap<String, Scope>,
+        pos: Option<(usize, usize)>,
+    ) -> Scope {
+        match param {
+            Parameter::Path(_) | Parameter::Name(_) => {
+                let name = param.as_name().unwrap_or_default().to_string();
+                self.resolve(&name, scopes, locals, pos)
+            }
+            Parameter::Literal(value) => Some(value.clone()),
+            Parameter::Subexpression(sub) => {
+                if let TemplateElement::Expression(ht) = sub.element.as_ref() {
+                    self.check_helper_call(ht, scopes, locals, pos);
+                }
+                None
+            }
+        }
+    }
+
+    // 检查helper名称和参数，返回第一个参数的值
+    fn check_helper_call(
+        &mut self,
+        ht: &HelperTemplate,
+        scopes: &[Scope],
+        locals: &HashMap<String, Scope>,
+        pos: Option<(usize, usize)>,
+    ) -> Scope {
+        let name = ht.name.as_name().unwrap_or_default().to_string();
+        if !self.is_helper(&name) {
+            self.issues.push(issue("error", format!("未知的helper: {}", name), pos));
+        }
+        let mut first = None;
+        for (i, param) in ht.params.iter().enumerate() {
+            let value = self.check_param(param, scopes, locals, pos);
+            if i == 0 {
+                first = value;
+            }
+        }
+        for param in ht.hash.values() {
+            self.check_param(param, scopes, locals, pos);
+        }
+        first
+    }
+
+    fn check_template(&mut self, template: &Template, scopes: &mut Vec<Scope>, locals: &HashMap<String, Scope>) {
+        for (i, element) in template.elements.iter().enumerate() {
+            let pos = template.mapping.get(i).map(|m| (m.0, m.1));
+            match element {
+                TemplateElement::Expression(ht) | TemplateElement::HtmlExpression(ht) => {
+                    let name = ht.name.as_name().unwrap_or_default();
+                    if ht.params.is_empty() && ht.hash.is_empty() && !self.is_helper(name) {
+                        self.check_param(&ht.name, scopes, locals, pos);
+                    } else {
+                        self.check_helper_call(ht, scopes, locals, pos);
+                    }
+                }
+                TemplateElement::HelperBlock(ht) => {
+                    let first = self.check_helper_call(ht, scopes, locals, pos);
+                    let name = ht.name.as_name().unwrap_or_default();
+                    let inner_scope = match name {
+                        "each" => first_item(&first),
+                        "with" => first.clone(),
+                        _ => scopes.last().cloned().flatten(),
+                    };
+                    let mut inner_locals = locals.clone();
+                    let params = block_param_names(&ht.block_param);
+                    if let Some(item) = params.first() {
+                        inner_locals.insert(item.clone(), inner_scope.clone());
+                    }
+                    for index in params.iter().skip(1) {
+                        inner_locals.insert(index.clone(), None);
+                    }
+
+                    if let Some(inner) = &ht.template {
+                        scopes.push(inner_scope);
+                        self.check_template(inner, scopes, &inner_locals);
+                        scopes.pop();
+                    }
+                    if let Some(inverse) = &ht.inverse {
+                        self.check_template(inverse, scopes, locals);
+                    }
+                }
+                TemplateElement::PartialExpression(dt) | TemplateElement::PartialBlock(dt) => {
+                    let name = dt.name.as_name().unwrap_or_default();
+                    if name != "@partial-block" && !self.partials.contains(name) {
+                        self.issues.push(issue("error", format!("未知的公共片段: {}", name), pos));
+                    }
+                    if let Some(inner) = &dt.template {
+                        self.check_template(inner, scopes, locals);
+                    }
+                }
+                TemplateElement::DecoratorBlock(dt) => {
+                    // {{#*inline "name"}} 定义的片段在后面可以直接引用
+                    if dt.name.as_name() == Some("inline") {
+                        if let Some(Parameter::Literal(Value::String(name))) = dt.params.first() {
+                            self.partials.insert(name.clone());
+                        }
+                    }
+                    if let Some(inner) = &dt.template {
+                        self.check_template(inner, scopes, locals);
+                    }
+                }
+                TemplateElement::RawString(_)
+                | TemplateElement::Comment(_)
+                | TemplateElement::DecoratorExpression(_) => {}
+            }
+        }
+    }
+}
+
+/// 检查模板：先编译报告语法错误的行列号，再按示例上下文检查变量、helper和公共片段
+///
+/// `kind` 为 None 时（公共片段）只检查语法和helper，因为片段的上下文取决于引用它的位置
+pub fn validate_template(kind: Option<&str>, content: &str) -> Result<TemplateValidation, String> {
+    if let Some(kind) = kind {
+        if !TEMPLATE_KINDS.contains(&kind) {
+            return Err(format!("不支持的模板类型: {}", kind));
+        }
+    }
+
+    let template = match Template::compile(content) {
+        Ok(template) => template,
+        Err(e) => {
+            let pos = e.line_no.zip(e.column_no);
+            return Ok(TemplateValidation {
+                valid: false,
+                issues: vec![issue("error", format!("模板语法错误: {}", e.reason()), pos)],
+            });
+        }
+    };
+
+    let mut checker = Checker {
+        helpers: BUILTIN_HELPERS.iter().chain(CUSTOM_HELPERS.iter()).map(|h| h.to_string()).collect(),
+        partials: TemplateManager::new().partials().into_iter().map(|p| p.name).collect(),
+        issues: Vec::new(),
+    };
+    let mut scopes = vec![kind.map(sample_data)];
+    checker.check_template(&template, &mut scopes, &HashMap::new());
+
+    let valid = !checker.issues.iter().any(|i| i.severity == "error");
+    Ok(TemplateValidation { valid, issues: checker.issues })
+}
+
+/// 保存前检查模板，有错误时拒绝保存，`force` 为 true 时跳过
+pub fn ensure_valid(kind: Option<&str>, content: &str, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+    let validation = validate_template(kind, content)?;
+    if validation.valid {
+        return Ok(());
+    }
+    let errors: Vec<String> = validation
+        .issues
+        .iter()
+        .filter(|i| i.severity == "error")
+        .map(|i| match (i.line, i.column) {
+            (Some(line), Some(column)) => format!("第{}行第{}列 {}", line, column, i.message),
+            _ => i.message.clone(),
+        })
+        .collect();
+    Err(format!("模板有误，未保存：{}", errors.join("；")))
+}
@@ -0,0 +1,138 @@
+use chrono::{Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use crate::database::calendar::week_range;
+use crate::database::sqlite::{init_db, load_latest_dailies_between};
+use crate::database::types::UserInfo;
+use super::context::{build_weekly_context, ContextOptions, WeeklyContext};
+use super::template_manager::{TemplateManager, DEFAULT_TEMPLATE_NAME};
+use super::report_impl::write_report_file;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 生成周报的参数，`iso_week` 与 `start_date`/`end_date` 二选一
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct WeeklyReportOptions {
+    pub iso_week: Option<String>,   // 2025-W24
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub user_id: Option<String>,
+    pub template_name: Option<String>, // 周报模板名称，默认 default
+    pub next_week_plan: Option<String>,
+    pub output_path: Option<String>, // 为空时只返回内容，不写文件
+    pub user_info: Option<UserInfo>,
+    pub repo_paths: Vec<String>,
+}
+
+/// 生成的周报
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WeeklyReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub iso_year: i32,
+    pub iso_week: u32,
+    pub content: String,
+    pub output_path: Option<String>,
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .map_err(|_| format!("日期格式不正确: {}, 应为YYYY-MM-DD格式", value))
+}
+
+// 解析 "2025-W24"、"2025W24" 或 "2025-24"
+fn parse_iso_week(value: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let invalid = || format!("周数格式不正确: {}, 应为YYYY-Www格式", value);
+    let upper = value.trim().to_uppercase();
+    let (year, week) = upper
+        .split_once('W')
+        .or_else(|| upper.split_once('-'))
+        .ok_or_else(invalid)?;
+    let (year, week) = (year.trim_end_matches('-'), week.trim_start_matches('-'));
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let week: u32 = week.parse().map_err(|_| invalid())?;
+    let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+        .ok_or_else(|| format!("{}年没有第{}周", year, week))?;
+    Ok((monday, monday + Duration::days(6)))
+}
+
+/// 根据ISO周数或起止日期确定周报范围，都没有提供时使用本周
+pub fn resolve_week_range(
+    iso_week: Option<&str>,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    if let Some(week) = iso_week.filter(|w| !w.trim().is_empty()) {
+        return parse_iso_week(week);
+    }
+    match (start_date, end_date) {
+        (Some(start), Some(end)) => {
+            let (start, end) = (parse_date(start)?, parse_date(end)?);
+            if start > end {
+                return Err("开始日期不能晚于结束日期".to_string());
+            }
+            Ok((start, end))
+        }
+        (Some(start), None) => Ok(week_range(parse_date(start)?)),
+        (None, Some(end)) => Ok(week_range(parse_date(end)?)),
+        (None, None) => Ok(week_range(chrono::Local::now().date_naive())),
+    }
+}
+
+/// 读取范围内和上一周的日报，构建周报模板上下文
+pub fn build_weekly_report_context(options: &WeeklyReportOptions) -> Result<WeeklyContext, String> {
+    let (start, end) = resolve_week_range(
+        options.iso_week.as_deref(),
+        options.start_date.as_deref(),
+        options.end_date.as_deref(),
+    )?;
+    let start_str = start.format(DATE_FORMAT).to_string();
+    let end_str = end.format(DATE_FORMAT).to_string();
+    let (prev_start, prev_end) = week_range(start - Duration::days(7));
+
+    let conn = init_db().map_err(|e| e.to_string())?;
+    let user_id = options.user_id.as_deref();
+    let dailies = load_latest_dailies_between(&conn, &start_str, &end_str, user_id)?;
+    let previous = load_latest_dailies_between(
+        &conn,
+        &prev_start.format(DATE_FORMAT).to_string(),
+        &prev_end.format(DATE_FORMAT).to_string(),
+        user_id,
+    )?;
+
+    let context_options = ContextOptions {
+        user_info: options.user_info.clone(),
+        repo_paths: options.repo_paths.clone(),
+    };
+    build_weekly_context(
+        &start_str,
+        &end_str,
+        &dailies,
+        &previous,
+        options.next_week_plan.clone(),
+        &context_options,
+    )
+}
+
+/// 汇总范围内的日报，通过周报模板生成周报
+///
+/// 提供 `output_path` 时同时写入文件，否则只返回内容由前端处理
+pub fn generate_weekly_report(options: &WeeklyReportOptions) -> Result<WeeklyReport, String> {
+    let context = build_weekly_report_context(options)?;
+    let data = serde_json::to_value(&context).map_err(|e| e.to_string())?;
+    let template_name = options.template_name.as_deref().unwrap_or(DEFAULT_TEMPLATE_NAME);
+    let content = TemplateManager::new().render("weekly", template_name, &data)?;
+
+    if let Some(path) = &options.output_path {
+        write_report_file(path, &content)?;
+    }
+
+    Ok(WeeklyReport {
+        start_date: context.start_date,
+        end_date: context.end_date,
+        iso_year: context.iso_year,
+        iso_week: context.iso_week,
+        content,
+        output_path: options.output_path.clone(),
+    })
+}
@@ -0,0 +1,118 @@
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+
+// 向后查找下一次触发时间的最大天数，足以覆盖"2月29日"这类隔年才出现的表达式
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 8;
+
+/// 标准5段cron表达式：分 时 日 月 周
+///
+/// 每段支持 `*`、数字、范围 `1-5`、列表 `1,3,5` 和步长 `*/15`、`0-30/10`；
+/// 周取值 0-7，0和7都表示周日。日和周同时限定时，满足其一即触发（与crontab一致）。
+#[derive(Clone, Debug)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+    dom_any: bool,
+    dow_any: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<u32>, String> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("{}字段的步长无效: {}", name, part))?;
+                if step == 0 {
+                    return Err(format!("{}字段的步长不能为0: {}", name, part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start.parse().map_err(|_| format!("{}字段的取值无效: {}", name, part))?;
+            let end = end.parse().map_err(|_| format!("{}字段的取值无效: {}", name, part))?;
+            (start, end)
+        } else {
+            let value: u32 = range.parse().map_err(|_| format!("{}字段的取值无效: {}", name, part))?;
+            // "5/10" 表示从5开始每10个单位
+            if part.contains('/') { (value, max) } else { (value, value) }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("{}字段超出范围 {}-{}: {}", name, min, max, part));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("cron表达式应包含5个字段（分 时 日 月 周）: {}", expr));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, "周")?;
+        if days_of_week.contains(&7) {
+            days_of_week.retain(|d| *d != 7);
+            if !days_of_week.contains(&0) {
+                days_of_week.insert(0, 0);
+            }
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(fields[0], 0, 59, "分")?,
+            hours: parse_field(fields[1], 0, 23, "时")?,
+            days_of_month: parse_field(fields[2], 1, 31, "日")?,
+            months: parse_field(fields[3], 1, 12, "月")?,
+            days_of_week,
+            dom_any: fields[2] == "*",
+            dow_any: fields[4] == "*",
+        })
+    }
+
+    fn matches_day(&self, date: chrono::NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&date.day());
+        let dow = self.days_of_week.contains(&date.weekday().num_days_from_sunday());
+        match (self.dom_any, self.dow_any) {
+            (true, true) => true,
+            (false, true) => dom,
+            (true, false) => dow,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// 严格晚于 `after` 的下一次触发时间（精确到分钟）
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..MAX_LOOKAHEAD_DAYS {
+            if self.matches_day(date) {
+                for hour in &self.hours {
+                    for minute in &self.minutes {
+                        let candidate = date.and_hms_opt(*hour, *minute, 0)?;
+                        if candidate >= start {
+                            return Some(candidate);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::Path;
+use chrono::{Datelike, Local, NaiveDateTime};
+use crate::database::types::UserInfo;
+use crate::database::sqlite::{init_db, load_dailies_between};
+use crate::database::report::{generate_weekly_report, WeeklyReportOptions};
+use crate::database::excel::{export_weekly_excel, generate_monthly_report, generate_weekly_from_daily, weekly_file_name};
+use crate::database::excel::output::{ExportOutput, ExportSink};
+use crate::database::calendar::week_range;
+use crate::database::supabase::save_daily_to_supabase;
+use crate::database::task_import::imported_daily_ids;
+use super::scheduler_impl::{register_job, ScheduledJob};
+
+fn payload_str(job: &ScheduledJob, key: &str) -> Option<String> {
+    job.payload.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+// 生成触发时间前一天所在那一周的周报，payload 可指定 output_path 和 template_name
+fn run_weekly(job: &ScheduledJob, scheduled_at: NaiveDateTime) -> Result<(), String> {
+    let yesterday = scheduled_at.date() - chrono::Duration::days(1);
+    let options = WeeklyReportOptions {
+        start_date: Some(yesterday.format("%Y-%m-%d").to_string()),
+        template_name: payload_str(job, "template_name"),
+        output_path: Some(payload_str(job, "output_path").unwrap_or_else(|| "weekly.md".to_string())),
+        ..Default::default()
+    };
+    let report = generate_weekly_report(options)?;
+    eprintln!("已生成周报 {} ~ {}", report.start_date, report.end_date);
+    Ok(())
+}
+
+// 把触发时间前一天所在那一周的日报汇总为Excel周报，payload.sink 为输出方式（与 generate_excel_report 相同），
+// 未设置时保存到 payload.output_dir（默认当前目录）
+fn run_weekly_excel(job: &ScheduledJob, scheduled_at: NaiveDateTime) -> Result<(), String> {
+    let yesterday = scheduled_at.date() - chrono::Duration::days(1);
+    let (start, end) = week_range(yesterday);
+    let start_date = start.format("%Y-%m-%d").to_string();
+    let end_date = end.format("%Y-%m-%d").to_string();
+
+    let sink = match job.payload.get("sink") {
+        Some(sink) => serde_json::from_value(sink.clone()).map_err(|e| format!("输出方式配置无效: {}", e))?,
+        None => {
+            let output_dir = payload_str(job, "output_dir").unwrap_or_else(|| ".".to_string());
+            let path = Path::new(&output_dir).join(weekly_file_name(&start_date, &end_date));
+            ExportSink::Path { path: path.to_string_lossy().to_string() }
+        }
+    };
+
+    let report = generate_weekly_from_daily(start_date.clone(), end_date.clone())?;
+    match export_weekly_excel(&start_date, &end_date, report.tasks, &report.next_week_plan, &sink)? {
+        ExportOutput::Saved { path } => eprintln!("已生成Excel周报: {}", path),
+        ExportOutput::Sent { results } => {
+            let failed = results.iter().filter(|r| !r.success).count();
+            if failed > 0 {
+                return Err(format!("{} 个渠道发送Excel周报失败", failed));
+            }
+        }
+        ExportOutput::Bytes { .. } => return Err("定时任务不支持返回文件内容，请配置 path 或 notify 输出方式".to_string()),
+    }
+    Ok(())
+}
+
+// 生成触发时间上个月的月报，payload 需要提供 template_path、output_dir 和 user_info
+fn run_monthly(job: &ScheduledJob, scheduled_at: NaiveDateTime) -> Result<(), String> {
+    let template_path = payload_str(job, "template_path").ok_or("月报任务未配置模板路径 template_path")?;
+    let output_dir = payload_str(job, "output_dir").ok_or("月报任务未配置输出目录 output_dir")?;
+
+    let today = scheduled_at.date();
+    let (year, month) = if today.month() == 1 { (today.year() - 1, 12) } else { (today.year(), today.month() - 1) };
+    let year_month = format!("{:04}-{:02}", year, month);
+
+    let user = job.payload.get("user_info").cloned().unwrap_or_default();
+    let field = |key: &str| user.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let user_info = UserInfo {
+        position: field("position"),
+        department: field("department"),
+        name: field("name"),
+        date: year_month.clone(),
+    };
+
+    let output_path = Path::new(&output_dir).join(format!("月报_{}.xlsx", year_month));
+    generate_monthly_report(
+        template_path,
+        output_path.to_string_lossy().to_string(),
+        year_month,
+        user_info,
+    )
+}
+
+// 把最近几天（payload.days，默认7天）的本地日报推送到Supabase，弥补保存时网络失败的情况；
+// 从Excel补录的日报可以随导入批次撤销，不会同步
+fn run_sync(job: &ScheduledJob, _scheduled_at: NaiveDateTime) -> Result<(), String> {
+    let days = job.payload.get("days").and_then(|v| v.as_i64()).unwrap_or(7);
+    let today = Local::now().date_naive();
+    let start = today - chrono::Duration::days(days);
+
+    let conn = init_db().map_err(|e| e.to_string())?;
+    let mut reports = load_dailies_between(
+        &conn,
+        &start.format("%Y-%m-%d").to_string(),
+        &today.format("%Y-%m-%d").to_string(),
+        None,
+    )?;
+    let imported = imported_daily_ids()?;
+    reports.retain(|report| !report.id.is_some_and(|id| imported.contains(&id)));
+
+    let mut failed = 0;
+    for report in &reports {
+        if let Err(e) = tauri::async_runtime::block_on(save_daily_to_supabase(report)) {
+            eprintln!("同步 {} 的日报失败: {}", report.date, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{} 篇日报同步失败", failed));
+    }
+    Ok(())
+}
+
+/// 注册数据库模块自带的定时任务
+pub fn register_builtin_jobs() {
+    // 每周日凌晨1点生成周报
+    let mut weekly = ScheduledJob::new("weekly", "生成周报", "0 1 * * 0", "weekly");
+    weekly.payload = serde_json::json!({ "output_path": "weekly.md" });
+    register_job(weekly, run_weekly);
+
+    // 每周日凌晨1点半生成Excel周报，默认不启用
+    let mut weekly_excel = ScheduledJob::new("weekly_excel", "生成Excel周报", "30 1 * * 0", "weekly_excel");
+    weekly_excel.enabled = false;
+    weekly_excel.payload = serde_json::json!({ "output_dir": "." });
+    register_job(weekly_excel, run_weekly_excel);
+
+    // 每月1日凌晨2点生成上月月报，需要先在任务参数中配置模板，默认不启用
+    let mut monthly = ScheduledJob::new("monthly", "生成月报", "0 2 1 * *", "monthly");
+    monthly.enabled = false;
+    register_job(monthly, run_monthly);
+
+    // 每小时补传最近几天的日报，需要用户开启
+    let mut sync = ScheduledJob::new("sync", "同步日报到云端", "15 * * * *", "sync");
+    sync.enabled = false;
+    sync.payload = serde_json::json!({ "days": 7 });
+    register_job(sync, run_sync);
+}
@@ -0,0 +1,35 @@
+// Scheduler module
+mod cron;
+mod jobs;
+mod scheduler_impl;
+#[cfg(test)]
+mod tests;
+
+pub use scheduler_impl::{ScheduledJob, UpcomingRun, register_job, start_scheduler};
+
+#[tauri::command]
+pub fn list_scheduled_jobs() -> Result<Vec<ScheduledJob>, String> {
+    scheduler_impl::list_scheduled_jobs()
+}
+
+#[tauri::command]
+pub fn save_scheduled_job(job: ScheduledJob) -> Result<(), String> {
+    scheduler_impl::save_scheduled_job(job)
+}
+
+#[tauri::command]
+pub fn delete_scheduled_job(id: String) -> Result<(), String> {
+    scheduler_impl::delete_scheduled_job(id)
+}
+
+#[tauri::command]
+pub async fn run_scheduled_job_now(id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || scheduler_impl::run_scheduled_job_now(&id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn list_upcoming_runs(limit: Option<usize>) -> Result<Vec<UpcomingRun>, String> {
+    scheduler_impl::list_upcoming_runs(limit.unwrap_or(20))
+}
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::{thread, time::Duration};
+use chrono::{Local, NaiveDateTime};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::database::sqlite::init_db;
+use crate::database::calendar::WorkCalendar;
+use super::cron::CronSchedule;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// 任务处理函数，参数为任务定义（可从 payload 读取任务参数）和本次对应的计划触发时间
+///
+/// 补跑时触发时间是错过的那一次，立即执行时是当前时间，按周期生成的报告应以它为准
+pub type JobHandler = Arc<dyn Fn(&ScheduledJob, NaiveDateTime) -> Result<(), String> + Send + Sync>;
+
+static HANDLERS: OnceLock<Mutex<HashMap<String, JobHandler>>> = OnceLock::new();
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 定时任务定义
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub name: String,
+    pub cron: String,
+    pub kind: String, // 对应已注册的处理函数，比如 weekly / monthly / sync / reminder
+    pub enabled: bool,
+    #[serde(default)]
+    pub workdays_only: bool, // 只在工作日（按节假日与调休安排）执行
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub last_status: Option<String>, // success / failed
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl ScheduledJob {
+    pub fn new(id: &str, name: &str, cron: &str, kind: &str) -> Self {
+        ScheduledJob {
+            id: id.to_string(),
+            name: name.to_string(),
+            cron: cron.to_string(),
+            kind: kind.to_string(),
+            enabled: true,
+            workdays_only: false,
+            payload: serde_json::Value::Null,
+            last_run_at: None,
+            last_status: None,
+            last_error: None,
+            created_at: None,
+        }
+    }
+}
+
+/// 即将执行的一次任务
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpcomingRun {
+    pub job_id: String,
+    pub name: String,
+    pub kind: String,
+    pub run_at: String,
+}
+
+fn handlers() -> &'static Mutex<HashMap<String, JobHandler>> {
+    HANDLERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn now_string(now: NaiveDateTime) -> String {
+    now.format(DATETIME_FORMAT).to_string()
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scheduled_jobs (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            cron TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            payload TEXT,
+            last_run_at TEXT,
+            last_status TEXT,
+            last_error TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+
+    let columns: Vec<String> = {
+        let mut stmt = conn.prepare("PRAGMA table_info(scheduled_jobs)").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1)).map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+    if !columns.contains(&"workdays_only".to_string()) {
+        conn.execute("ALTER TABLE scheduled_jobs ADD COLUMN workdays_only INTEGER NOT NULL DEFAULT 0", [])
+            .map_err(|e| e.to_string())?;
+    }
+
+    // 用户删除的任务，内置任务不会在下次启动时重新写入
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deleted_jobs (
+            id TEXT PRIMARY KEY,
+            deleted_at TEXT NOT NULL
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn load_jobs(conn: &Connection) -> Result<Vec<ScheduledJob>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, cron, kind, enabled, payload, last_run_at, last_status, last_error, created_at, workdays_only
+         FROM scheduled_jobs ORDER BY created_at ASC, id ASC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        let payload: Option<String> = row.get(5)?;
+        Ok(ScheduledJob {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            cron: row.get(2)?,
+            kind: row.get(3)?,
+            enabled: row.get(4)?,
+            payload: payload
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or(serde_json::Value::Null),
+            last_run_at: row.get(6)?,
+            last_status: row.get(7)?,
+            last_error: row.get(8)?,
+            created_at: row.get(9)?,
+            workdays_only: row.get(10)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+fn payload_text(job: &ScheduledJob) -> Option<String> {
+    if job.payload.is_null() {
+        None
+    } else {
+        Some(job.payload.to_string())
+    }
+}
+
+/// 注册某类任务的处理函数，同类任务共用一个处理函数
+pub fn register_job_handler<F>(kind: &str, handler: F)
+where
+    F: Fn(&ScheduledJob, NaiveDateTime) -> Result<(), String> + Send + Sync + 'static,
+{
+    if let Ok(mut map) = handlers().lock() {
+        map.insert(kind.to_string(), Arc::new(handler));
+    }
+}
+
+/// 注册处理函数，并在任务表中还没有该任务时写入默认定义
+///
+/// 已存在的任务保留用户修改过的cron、启用状态和参数，用户删除过的任务不再写入
+pub fn register_job<F>(default_job: ScheduledJob, handler: F)
+where
+    F: Fn(&ScheduledJob, NaiveDateTime) -> Result<(), String> + Send + Sync + 'static,
+{
+    register_job_handler(&default_job.kind, handler);
+    let result = open_db().and_then(|conn| {
+        conn.execute(
+            "INSERT OR IGNORE INTO scheduled_jobs (id, name, cron, kind, enabled, payload, created_at, workdays_only)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+             WHERE NOT EXISTS (SELECT 1 FROM deleted_jobs WHERE id = ?1)",
+            params![
+                default_job.id,
+                default_job.name,
+                default_job.cron,
+                default_job.kind,
+                default_job.enabled,
+                payload_text(&default_job),
+                now_string(Local::now().naive_local()),
+                default_job.workdays_only,
+            ],
+        ).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        eprintln!("注册定时任务 {} 失败: {}", default_job.id, e);
+    }
+}
+
+pub fn list_scheduled_jobs() -> Result<Vec<ScheduledJob>, String> {
+    let conn = open_db()?;
+    load_jobs(&conn)
+}
+
+pub fn save_scheduled_job(job: ScheduledJob) -> Result<(), String> {
+    if job.id.trim().is_empty() {
+        return Err("任务ID不能为空".to_string());
+    }
+    CronSchedule::parse(&job.cron)?;
+
+    let conn = open_db()?;
+    // 修改已有任务时保留执行记录
+    conn.execute(
+        "INSERT INTO scheduled_jobs (id, name, cron, kind, enabled, payload, created_at, workdays_only)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name, cron = excluded.cron, kind = excluded.kind,
+            enabled = excluded.enabled, payload = excluded.payload, workdays_only = excluded.workdays_only",
+        params![
+            job.id,
+            job.name,
+            job.cron,
+            job.kind,
+            job.enabled,
+            payload_text(&job),
+            now_string(Local::now().naive_local()),
+            job.workdays_only,
+        ],
+    ).map_err(|e| format!("保存定时任务失败: {}", e))?;
+    conn.execute("DELETE FROM deleted_jobs WHERE id = ?1", [&job.id])
+        .map_err(|e| format!("保存定时任务失败: {}", e))?;
+    Ok(())
+}
+
+pub fn delete_scheduled_job(id: String) -> Result<(), String> {
+    let conn = open_db()?;
+    conn.execute("DELETE FROM scheduled_jobs WHERE id = ?1", [&id])
+        .map_err(|e| format!("删除定时任务失败: {}", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO deleted_jobs (id, deleted_at) VALUES (?1, ?2)",
+        params![id, now_string(Local::now().naive_local())],
+    ).map_err(|e| format!("删除定时任务失败: {}", e))?;
+    Ok(())
+}
+
+fn record_run(conn: &Connection, job: &ScheduledJob, now: NaiveDateTime, status: &str, error: Option<String>) -> Result<(), String> {
+    conn.execute(
+        "UPDATE scheduled_jobs SET last_run_at = ?1, last_status = ?2, last_error = ?3 WHERE id = ?4",
+        params![now_string(now), status, error, job.id],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn execute_job(conn: &Connection, job: &ScheduledJob, now: NaiveDateTime, scheduled_at: NaiveDateTime) -> Result<(), String> {
+    let handler = handlers()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&job.kind)
+        .cloned()
+        .ok_or_else(|| format!("没有为任务类型 {} 注册处理函数", job.kind))?;
+
+    let result = handler(job, scheduled_at);
+    match &result {
+        Ok(_) => record_run(conn, job, now, "success", None)?,
+        Err(e) => record_run(conn, job, now, "failed", Some(e.clone()))?,
+    }
+    result
+}
+
+fn has_handler(kind: &str) -> bool {
+    handlers().lock().map(|map| map.contains_key(kind)).unwrap_or(false)
+}
+
+// 判断任务是否到期：从上次执行（从未执行过则为创建时间）算起的下一次触发时间已经过去，
+// 到期时返回错过的最近一次触发时间
+//
+// 应用关闭期间错过的多次执行只补跑一次，按最近一次触发时间执行
+fn is_due(job: &ScheduledJob, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let baseline = job
+        .last_run_at
+        .as_ref()
+        .or(job.created_at.as_ref())
+        .and_then(|t| NaiveDateTime::parse_from_str(t, DATETIME_FORMAT).ok())
+        .unwrap_or(now);
+    let schedule = match CronSchedule::parse(&job.cron) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            eprintln!("任务 {} 的cron表达式无效: {}", job.id, e);
+            return None;
+        }
+    };
+    let mut due = None;
+    let mut cursor = baseline;
+    while let Some(next) = schedule.next_after(cursor).filter(|next| *next <= now) {
+        due = Some(next);
+        cursor = next;
+    }
+    due
+}
+
+/// 执行所有到期的任务，返回本次执行的任务数
+pub fn run_due_jobs(now: NaiveDateTime) -> Result<usize, String> {
+    let conn = open_db()?;
+    let calendar = WorkCalendar::load();
+    let mut count = 0;
+    for job in load_jobs(&conn)? {
+        // 没有注册处理函数的任务（比如只在桌面端可用的提醒）保持原样，不记为已执行
+        if !job.enabled || !has_handler(&job.kind) {
+            continue;
+        }
+        let scheduled_at = match is_due(&job, now) {
+            Some(scheduled_at) => scheduled_at,
+            None => continue,
+        };
+        // 应执行的那天是节假日时跳过本次执行，同时记为已处理，避免节后补跑
+        if job.workdays_only && !calendar.is_workday(scheduled_at.date()) {
+            record_run(&conn, &job, now, "skipped", None)?;
+            continue;
+        }
+        if let Err(e) = execute_job(&conn, &job, now, scheduled_at) {
+            eprintln!("定时任务 {} 执行失败: {}", job.name, e);
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// 立即执行指定任务
+pub fn run_scheduled_job_now(id: &str) -> Result<(), String> {
+    let conn = open_db()?;
+    let job = load_jobs(&conn)?
+        .into_iter()
+        .find(|j| j.id == id)
+        .ok_or_else(|| format!("找不到定时任务: {}", id))?;
+    let now = Local::now().naive_local();
+    execute_job(&conn, &job, now, now)
+}
+
+/// 列出所有启用任务接下来的执行时间，按时间排序
+pub fn list_upcoming_runs(limit: usize) -> Result<Vec<UpcomingRun>, String> {
+    let now = Local::now().naive_local();
+    let mut runs: Vec<(NaiveDateTime, UpcomingRun)> = Vec::new();
+    let calendar = WorkCalendar::load();
+    for job in list_scheduled_jobs()?.into_iter().filter(|j| j.enabled) {
+        let schedule = match CronSchedule::parse(&job.cron) {
+            Ok(schedule) => schedule,
+            Err(_) => continue,
+        };
+        let mut cursor = now;
+        let mut found = 0;
+        // 只在工作日执行的任务跳过节假日，最多向后查找有限次数
+        for _ in 0..limit * 10 {
+            if found >= limit {
+                break;
+            }
+            let run_at = match schedule.next_after(cursor) {
+                Some(run_at) => run_at,
+                None => break,
+            };
+            cursor = run_at;
+            if job.workdays_only && !calendar.is_workday(run_at.date()) {
+                continue;
+            }
+            found += 1;
+            runs.push((run_at, UpcomingRun {
+                job_id: job.id.clone(),
+                name: job.name.clone(),
+                kind: job.kind.clone(),
+                run_at: now_string(run_at),
+            }));
+        }
+    }
+    runs.sort_by_key(|(run_at, _)| *run_at);
+    Ok(runs.into_iter().take(limit).map(|(_, run)| run).collect())
+}
+
+/// 启动调度线程，重复调用只会启动一次
+///
+/// 启动时立即检查一次，补跑应用关闭期间错过的任务
+pub fn start_scheduler() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    super::jobs::register_builtin_jobs();
+
+    thread::spawn(|| loop {
+        if let Err(e) = run_due_jobs(Local::now().naive_local()) {
+            eprintln!("检查定时任务失败: {}", e);
+        }
+        thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+    });
+}
@@ -0,0 +1,63 @@
+// cron表达式解析和下一次触发时间的测试
+use chrono::{NaiveDate, NaiveDateTime};
+use super::cron::CronSchedule;
+
+fn at(date: &str, time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+fn next(expr: &str, after: NaiveDateTime) -> NaiveDateTime {
+    CronSchedule::parse(expr).unwrap().next_after(after).unwrap()
+}
+
+#[test]
+fn next_run_is_strictly_after() {
+    assert_eq!(next("*/15 * * * *", at("2025-06-10", "10:07:30")), at("2025-06-10", "10:15:00"));
+    assert_eq!(next("*/15 * * * *", at("2025-06-10", "10:15:00")), at("2025-06-10", "10:30:00"));
+    assert_eq!(next("0 0 * * *", at("2025-12-31", "23:59:59")), at("2026-01-01", "00:00:00"));
+}
+
+#[test]
+fn ranges_lists_and_steps() {
+    assert_eq!(next("5/20 * * * *", at("2025-06-10", "10:26:00")), at("2025-06-10", "10:45:00"));
+    assert_eq!(next("0-30/10 9 * * *", at("2025-06-10", "09:30:00")), at("2025-06-11", "09:00:00"));
+    assert_eq!(next("0 8,12,18 * * *", at("2025-06-10", "12:00:00")), at("2025-06-10", "18:00:00"));
+}
+
+#[test]
+fn weekday_fields() {
+    // 2025-06-13 是周五
+    assert_eq!(next("0 9 * * 1-5", at("2025-06-13", "09:00:00")), at("2025-06-16", "09:00:00"));
+    // 0 和 7 都表示周日
+    assert_eq!(next("0 1 * * 0", at("2025-06-10", "12:00:00")), at("2025-06-15", "01:00:00"));
+    assert_eq!(next("0 1 * * 7", at("2025-06-10", "12:00:00")), at("2025-06-15", "01:00:00"));
+}
+
+#[test]
+fn day_of_month_or_weekday() {
+    // 日和周同时限定时满足其一即可：6月的第一个周五早于13日
+    assert_eq!(next("0 0 13 * 5", at("2025-06-01", "00:00:00")), at("2025-06-06", "00:00:00"));
+    assert_eq!(next("0 0 13 * *", at("2025-06-01", "00:00:00")), at("2025-06-13", "00:00:00"));
+}
+
+#[test]
+fn rare_dates_are_found() {
+    assert_eq!(next("0 2 1 * *", at("2025-01-31", "03:00:00")), at("2025-02-01", "02:00:00"));
+    assert_eq!(next("0 0 29 2 *", at("2025-03-01", "00:00:00")), at("2028-02-29", "00:00:00"));
+    assert_eq!(next("0 0 31 * *", at("2025-04-01", "00:00:00")), at("2025-05-31", "00:00:00"));
+}
+
+#[test]
+fn impossible_dates_have_no_next_run() {
+    let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+    let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    assert_eq!(schedule.next_after(start), None);
+}
+
+#[test]
+fn invalid_expressions() {
+    for expr in ["* * * *", "* * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "* * * 13 *", "* * * * 8",
+                 "*/0 * * * *", "5-1 * * * *", "a * * * *", "1,,2 * * * *"] {
+        assert!(CronSchedule::parse(expr).is_err(), "{} 应该解析失败", expr);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use rusqlite::{Connection, Result};
 use crate::database::types::DailyReport;
 
@@ -113,6 +114,9 @@ pub fn init_db() -> Result<Connection> {
         if !columns.contains(&"user_id".to_string()) {
             conn.execute("ALTER TABLE dailies ADD COLUMN user_id TEXT", [])?;
         }
+        if !columns.contains(&"task_id".to_string()) {
+            conn.execute("ALTER TABLE dailies ADD COLUMN task_id TEXT", [])?;
+        }
     }
     
     // 添加：检查数据对应关系，进行字段修正
@@ -172,16 +176,82 @@ pub fn save_daily_report_to_sqlite(report: &DailyReport) -> Result<(), String> {
     let conn = init_db().map_err(|e| e.to_string())?;
     
     conn.execute(
-        "INSERT INTO dailies (user_id, date, content, should, done, undone) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        [
+        "INSERT INTO dailies (user_id, date, content, should, done, undone, task_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
             &report.user_id.clone().unwrap_or_default(),
             &report.date,
             &report.remarks,
             &report.should_complete,
             &report.completed,
             &report.uncompleted,
+            &report.task_id,
         ],
     ).map_err(|e| e.to_string())?;
     
     Ok(())
 }
+
+// 查询日期范围内（含首尾）的日报，可选按用户过滤
+pub fn load_dailies_between(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    user_id: Option<&str>,
+) -> Result<Vec<DailyReport>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, user_id, date, content, should, done, undone, task_id FROM dailies
+         WHERE date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR user_id = ?3 OR user_id IS NULL OR user_id = '')
+         ORDER BY date ASC, id ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(rusqlite::params![start_date, end_date, user_id], |row| {
+        let user_id: Option<String> = row.get(1)?;
+        Ok(DailyReport {
+            id: row.get(0)?,
+            user_id: user_id.filter(|id| !id.is_empty()),
+            date: row.get(2)?,
+            task_id: row.get(7)?,
+            task_name: None,
+            should_complete: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            completed: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            uncompleted: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+            plan_hours: None,
+            actual_hours: None,
+            remarks: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        })
+    }).map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+fn is_blank_daily(report: &DailyReport) -> bool {
+    report.should_complete.trim().is_empty() && report.completed.trim().is_empty() && report.uncompleted.trim().is_empty()
+}
+
+/// 每次保存日报都会新增一行，按日期只保留最后保存的一份非空日报，结果按日期排序
+pub fn latest_dailies_by_date(reports: Vec<DailyReport>) -> Vec<DailyReport> {
+    let mut latest: BTreeMap<String, DailyReport> = BTreeMap::new();
+    for report in reports.into_iter().filter(|r| !is_blank_daily(r)) {
+        latest.insert(report.date.clone(), report);
+    }
+    latest.into_values().collect()
+}
+
+// 查询日期范围内每天最新的一份非空日报，汇总周报、月报等多天的数据时使用
+pub fn load_latest_dailies_between(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    user_id: Option<&str>,
+) -> Result<Vec<DailyReport>, String> {
+    Ok(latest_dailies_by_date(load_dailies_between(conn, start_date, end_date, user_id)?))
+}
+
+// 某一天最新的一份非空日报
+pub fn load_latest_daily(
+    conn: &Connection,
+    date: &str,
+    user_id: Option<&str>,
+) -> Result<Option<DailyReport>, String> {
+    Ok(load_latest_dailies_between(conn, date, date, user_id)?.pop())
+}
@@ -59,3 +59,7 @@ pub async fn test_supabase_connection() -> Result<String, String> {
 pub async fn get_dailies_from_supabase(user_id: Option<String>) -> Result<Vec<crate::database::types::DailyReport>, String> {
     supabase_impl::get_dailies_from_supabase(user_id).await
 } 
+
+pub async fn get_submission_dates_from_supabase(start_date: &str, end_date: &str) -> Result<Vec<(String, String)>, String> {
+    supabase_impl::get_submission_dates_from_supabase(start_date, end_date).await
+}
@@ -866,3 +866,46 @@ async fn get_daily_report_with_user_id(date: &str, user_id: &str) -> Result<Dail
         Err(error_text)
     }
 }
+
+#[derive(serde::Deserialize)]
+struct SubmissionRow {
+    user_id: Option<String>,
+    date: String,
+}
+
+// 查询日期范围内（含首尾）所有用户提交过日报的日期，返回 (用户ID, 日期)
+pub async fn get_submission_dates_from_supabase(start_date: &str, end_date: &str) -> Result<Vec<(String, String)>, String> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    let url = format!(
+        "{}/rest/v1/dailies?select=user_id,date&date=gte.{}&date=lte.{}&order=date.asc&limit=10000",
+        SUPABASE_URL, start_date, end_date
+    );
+    eprintln!("请求URL: {}", url);
+
+    let res = client
+        .get(&url)
+        .header("apikey", SUPABASE_KEY)
+        .header("Authorization", format!("Bearer {}", SUPABASE_KEY))
+        .send()
+        .await
+        .map_err(|e| format!("Supabase网络请求失败: {}", e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let error_text = res.text().await.unwrap_or_else(|_| "无法读取错误详情".to_string());
+        return Err(format!("查询日报提交记录失败: 状态码 {}, 错误: {}", status, error_text));
+    }
+
+    let rows: Vec<SubmissionRow> = res
+        .json()
+        .await
+        .map_err(|e| format!("解析Supabase响应失败: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.user_id.unwrap_or_else(|| "guest".to_string()), row.date))
+        .collect())
+}
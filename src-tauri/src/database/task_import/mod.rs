@@ -0,0 +1,26 @@
+// Task import module - 把Excel/CSV中的任务导入本地数据库，按批次撤销
+mod task_import_impl;
+
+pub use task_import_impl::{imported_daily_ids, ImportBatch, ImportResult, PlannedTask, TaskImportOptions};
+
+// Re-export with #[tauri::command] preservation
+// options.dry_run 为 true 时只检查重复等问题，不写入数据库
+#[tauri::command]
+pub fn import_tasks_to_db(file_data: Vec<u8>, options: Option<TaskImportOptions>) -> Result<ImportResult, String> {
+    task_import_impl::import_tasks_to_db(file_data, options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn list_import_batches() -> Result<Vec<ImportBatch>, String> {
+    task_import_impl::list_import_batches()
+}
+
+#[tauri::command]
+pub fn undo_import_batch(batch_id: i64) -> Result<usize, String> {
+    task_import_impl::undo_import_batch(batch_id)
+}
+
+#[tauri::command]
+pub fn list_planned_tasks(user_id: Option<String>) -> Result<Vec<PlannedTask>, String> {
+    task_import_impl::list_planned_tasks(user_id.as_deref())
+}
@@ -0,0 +1,418 @@
+use std::collections::{BTreeMap, HashSet};
+use chrono::{Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::database::excel::import_excel_tasks;
+use crate::database::excel::mapping::TableMapping;
+use crate::database::sqlite::init_db;
+use crate::database::tickets::TicketExtractor;
+use crate::database::types::Task;
+
+// 补录日报时可以指定的日期列
+const DATE_FIELDS: [&str; 4] = ["plan_start_time", "plan_end_time", "actual_start_time", "actual_end_time"];
+
+// 补录日报时默认按哪一列的日期归到哪一天，为空时依次使用后面的列
+const DEFAULT_DATE_FIELDS: [&str; 2] = ["actual_end_time", "plan_end_time"];
+
+/// 导入方式：作为计划任务保存，或按日期补录为日报
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    #[default]
+    Tasks,
+    Dailies,
+}
+
+impl ImportMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImportMode::Tasks => "tasks",
+            ImportMode::Dailies => "dailies",
+        }
+    }
+}
+
+/// 导入到本地数据库的选项，`sheet` 和 `mapping` 与 `import_excel_tasks` 相同
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TaskImportOptions {
+    pub mode: ImportMode,
+    pub sheet: Option<String>,
+    pub mapping: Option<TableMapping>,
+    pub user_id: Option<String>,
+    // 导入的文件名，显示在导入记录中
+    pub source: Option<String>,
+    // 补录日报时使用的日期列，默认实际结束时间，为空时使用计划结束时间
+    pub date_field: Option<String>,
+    // 为 true 时重复的记录也会导入
+    pub allow_duplicates: bool,
+    // 为 true 时只检查不写入，用于导入前确认
+    pub dry_run: bool,
+}
+
+/// 与已有记录重复的任务或日报
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImportDuplicate {
+    pub task: String,
+    pub date: Option<String>,
+    pub existing_id: i64,
+}
+
+/// 导入结果，试运行或没有导入任何记录时 `batch_id` 为空
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImportResult {
+    pub batch_id: Option<i64>,
+    pub imported: usize,
+    pub duplicates: Vec<ImportDuplicate>,
+    // 无法导入的行及原因
+    pub skipped: Vec<String>,
+}
+
+/// 一次导入的记录，撤销后 `undone_at` 不为空
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImportBatch {
+    pub id: i64,
+    pub mode: String,
+    pub source: Option<String>,
+    pub user_id: Option<String>,
+    pub row_count: i64,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+/// 导入的计划任务
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlannedTask {
+    pub id: i64,
+    pub batch_id: i64,
+    pub user_id: Option<String>,
+    #[serde(flatten)]
+    pub task: Task,
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS import_batches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            mode TEXT NOT NULL,
+            source TEXT,
+            user_id TEXT,
+            row_count INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            undone_at TEXT
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS planned_tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batch_id INTEGER NOT NULL,
+            user_id TEXT,
+            task_id TEXT,
+            task_name TEXT,
+            task TEXT NOT NULL,
+            status TEXT,
+            remarks TEXT,
+            plan_start_time TEXT,
+            plan_end_time TEXT,
+            actual_start_time TEXT,
+            actual_end_time TEXT,
+            plan_hours TEXT,
+            actual_hours TEXT
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+
+    // 补录的日报记录所属的导入批次，撤销时按批次删除
+    let columns = conn.prepare("PRAGMA table_info(dailies)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| e.to_string())?;
+    if !columns.contains(&"import_batch_id".to_string()) {
+        conn.execute("ALTER TABLE dailies ADD COLUMN import_batch_id INTEGER", [])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+/// 读取Excel/CSV中的任务并写入本地数据库，整个导入记为一个批次，可以整体撤销
+///
+/// 与已有记录重复的行默认跳过并在结果中列出
+pub fn import_tasks_to_db(file_data: Vec<u8>, options: TaskImportOptions) -> Result<ImportResult, String> {
+    if let Some(field) = options.date_field.as_deref().filter(|f| !f.trim().is_empty()) {
+        if !DATE_FIELDS.contains(&field) {
+            return Err(format!("不支持的日期列: {}，可选 {}", field, DATE_FIELDS.join("、")));
+        }
+    }
+    let tasks = import_excel_tasks(file_data, options.sheet.clone(), options.mapping.clone())?;
+    if tasks.is_empty() {
+        return Err("文件中没有可导入的任务".to_string());
+    }
+
+    let mut conn = open_db()?;
+    let tx = conn.transaction().map_err(|e| format!("开始导入失败: {}", e))?;
+    let user_id = options.user_id.clone().filter(|id| !id.trim().is_empty());
+    tx.execute(
+        "INSERT INTO import_batches (mode, source, user_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![options.mode.as_str(), options.source, user_id, Local::now().to_rfc3339()],
+    ).map_err(|e| format!("创建导入记录失败: {}", e))?;
+    let batch_id = tx.last_insert_rowid();
+
+    let mut result = ImportResult {
+        batch_id: Some(batch_id),
+        imported: 0,
+        duplicates: Vec::new(),
+        skipped: Vec::new(),
+    };
+    match options.mode {
+        ImportMode::Tasks => insert_planned_tasks(&tx, batch_id, &tasks, &options, &mut result)?,
+        ImportMode::Dailies => insert_dailies(&tx, batch_id, &tasks, &options, &mut result)?,
+    }
+    tx.execute(
+        "UPDATE import_batches SET row_count = ?1 WHERE id = ?2",
+        params![result.imported as i64, batch_id],
+    ).map_err(|e| format!("更新导入记录失败: {}", e))?;
+
+    if options.dry_run || result.imported == 0 {
+        // 事务没有提交，丢弃时自动回滚；全部重复时也不留下空的导入记录
+        result.batch_id = None;
+        return Ok(result);
+    }
+    tx.commit().map_err(|e| format!("保存导入结果失败: {}", e))?;
+    Ok(result)
+}
+
+fn insert_planned_tasks(
+    conn: &Connection,
+    batch_id: i64,
+    tasks: &[Task],
+    options: &TaskImportOptions,
+    result: &mut ImportResult,
+) -> Result<(), String> {
+    let user_id = options.user_id.clone().unwrap_or_default();
+    for task in tasks {
+        // 同一用户下任务内容、编号和计划开始时间都相同视为重复
+        let existing: Option<i64> = conn.query_row(
+            "SELECT id FROM planned_tasks
+             WHERE IFNULL(user_id, '') = ?1 AND task = ?2 AND IFNULL(task_id, '') = ?3 AND IFNULL(plan_start_time, '') = ?4
+             LIMIT 1",
+            params![
+                user_id,
+                task.task,
+                task.task_id.clone().unwrap_or_default(),
+                task.plan_start_time.clone().unwrap_or_default(),
+            ],
+            |row| row.get(0),
+        ).optional().map_err(|e| format!("检查重复任务失败: {}", e))?;
+        if let Some(existing_id) = existing {
+            result.duplicates.push(ImportDuplicate {
+                task: task.task.clone(),
+                date: task.plan_start_time.clone(),
+                existing_id,
+            });
+            if !options.allow_duplicates {
+                continue;
+            }
+        }
+
+        conn.execute(
+            "INSERT INTO planned_tasks (batch_id, user_id, task_id, task_name, task, status, remarks,
+                plan_start_time, plan_end_time, actual_start_time, actual_end_time, plan_hours, actual_hours)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                batch_id,
+                options.user_id.as_deref().filter(|id| !id.trim().is_empty()),
+                task.task_id,
+                task.task_name,
+                task.task,
+                task.status,
+                task.remarks,
+                task.plan_start_time,
+                task.plan_end_time,
+                task.actual_start_time,
+                task.actual_end_time,
+                task.plan_hours,
+                task.actual_hours,
+            ],
+        ).map_err(|e| format!("保存任务失败: {}", e))?;
+        result.imported += 1;
+    }
+    Ok(())
+}
+
+// 任务所属的日期：指定了日期列时只用该列，否则依次尝试实际结束时间和计划结束时间
+fn task_date(task: &Task, date_field: Option<&str>) -> Option<String> {
+    let value = |field: &str| match field {
+        "plan_start_time" => task.plan_start_time.clone(),
+        "plan_end_time" => task.plan_end_time.clone(),
+        "actual_start_time" => task.actual_start_time.clone(),
+        "actual_end_time" => task.actual_end_time.clone(),
+        _ => None,
+    };
+    match date_field.filter(|f| !f.trim().is_empty()) {
+        Some(field) => value(field),
+        None => DEFAULT_DATE_FIELDS.iter().find_map(|field| value(field)),
+    }
+    .filter(|date| !date.trim().is_empty())
+}
+
+// 按日期把任务合并为日报：全部任务写入应完成，已完成的写入已完成，其余（已取消除外）写入未完成
+fn insert_dailies(
+    conn: &Connection,
+    batch_id: i64,
+    tasks: &[Task],
+    options: &TaskImportOptions,
+    result: &mut ImportResult,
+) -> Result<(), String> {
+    let mut by_date: BTreeMap<String, Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        let date = task_date(task, options.date_field.as_deref());
+        match date.filter(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok()) {
+            Some(date) => by_date.entry(date).or_default().push(task),
+            None => result.skipped.push(format!("没有有效的日期，跳过: {}", task.task)),
+        }
+    }
+
+    let extractor = TicketExtractor::from_config();
+    let user_id = options.user_id.as_deref().filter(|id| !id.trim().is_empty());
+    for (date, tasks) in by_date {
+        // 同一用户当天已有日报视为重复，不覆盖手写的日报
+        let existing: Option<i64> = conn.query_row(
+            "SELECT id FROM dailies WHERE date = ?1 AND (?2 IS NULL OR user_id = ?2 OR user_id IS NULL OR user_id = '') LIMIT 1",
+            params![date, user_id],
+            |row| row.get(0),
+        ).optional().map_err(|e| format!("检查重复日报失败: {}", e))?;
+        if let Some(existing_id) = existing {
+            result.duplicates.extend(tasks.iter().map(|task| ImportDuplicate {
+                task: task.task.clone(),
+                date: Some(date.clone()),
+                existing_id,
+            }));
+            if !options.allow_duplicates {
+                continue;
+            }
+        }
+
+        let lines = |filter: &dyn Fn(&Task) -> bool| {
+            tasks.iter().filter(|t| filter(t)).map(|t| t.task.clone()).collect::<Vec<_>>().join("\n")
+        };
+        let should = lines(&|_| true);
+        let done = lines(&|t| t.status == "已完成");
+        let undone = lines(&|t| t.status != "已完成" && t.status != "已取消");
+        let remarks = tasks
+            .iter()
+            .filter(|t| !t.remarks.trim().is_empty())
+            .map(|t| format!("{}：{}", t.task, t.remarks.trim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut task_ids: Vec<String> = Vec::new();
+        for id in tasks.iter().filter_map(|t| t.task_id.clone()) {
+            if !task_ids.contains(&id) {
+                task_ids.push(id);
+            }
+        }
+        let task_id = if task_ids.is_empty() { extractor.task_id_for(&should) } else { Some(task_ids.join(",")) };
+
+        conn.execute(
+            "INSERT INTO dailies (user_id, date, content, should, done, undone, task_id, import_batch_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![user_id.unwrap_or_default(), date, remarks, should, done, undone, task_id, batch_id],
+        ).map_err(|e| format!("保存日报失败: {}", e))?;
+        result.imported += 1;
+    }
+    Ok(())
+}
+
+pub fn list_import_batches() -> Result<Vec<ImportBatch>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, mode, source, user_id, row_count, created_at, undone_at FROM import_batches ORDER BY id DESC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ImportBatch {
+            id: row.get(0)?,
+            mode: row.get(1)?,
+            source: row.get(2)?,
+            user_id: row.get(3)?,
+            row_count: row.get(4)?,
+            created_at: row.get(5)?,
+            undone_at: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// 导入时补录的日报id，这些日报随导入批次撤销，不同步到Supabase
+pub fn imported_daily_ids() -> Result<HashSet<i64>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn
+        .prepare("SELECT id FROM dailies WHERE import_batch_id IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+    rows.collect::<Result<HashSet<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// 撤销一次导入：删除该批次写入的计划任务和补录的本地日报，返回删除的记录数
+///
+/// 补录的日报只保存在本地，同步任务会跳过它们（见 `imported_daily_ids`），因此不需要远程删除
+pub fn undo_import_batch(batch_id: i64) -> Result<usize, String> {
+    let mut conn = open_db()?;
+    let tx = conn.transaction().map_err(|e| format!("撤销导入失败: {}", e))?;
+    let undone_at: Option<Option<String>> = tx.query_row(
+        "SELECT undone_at FROM import_batches WHERE id = ?1",
+        params![batch_id],
+        |row| row.get(0),
+    ).optional().map_err(|e| e.to_string())?;
+    match undone_at {
+        None => return Err(format!("没有找到导入记录: {}", batch_id)),
+        Some(Some(at)) => return Err(format!("该导入已于 {} 撤销", at)),
+        Some(None) => {}
+    }
+
+    let tasks = tx.execute("DELETE FROM planned_tasks WHERE batch_id = ?1", params![batch_id])
+        .map_err(|e| format!("删除导入的任务失败: {}", e))?;
+    let dailies = tx.execute("DELETE FROM dailies WHERE import_batch_id = ?1", params![batch_id])
+        .map_err(|e| format!("删除导入的日报失败: {}", e))?;
+    tx.execute(
+        "UPDATE import_batches SET undone_at = ?1 WHERE id = ?2",
+        params![Local::now().to_rfc3339(), batch_id],
+    ).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("撤销导入失败: {}", e))?;
+    Ok(tasks + dailies)
+}
+
+/// 导入的计划任务，可按用户过滤（没有用户的任务总是包含在内）
+pub fn list_planned_tasks(user_id: Option<&str>) -> Result<Vec<PlannedTask>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, batch_id, user_id, task_id, task_name, task, status, remarks,
+                plan_start_time, plan_end_time, actual_start_time, actual_end_time, plan_hours, actual_hours
+         FROM planned_tasks
+         WHERE ?1 IS NULL OR user_id = ?1 OR user_id IS NULL
+         ORDER BY IFNULL(plan_start_time, ''), id"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![user_id], |row| {
+        Ok(PlannedTask {
+            id: row.get(0)?,
+            batch_id: row.get(1)?,
+            user_id: row.get(2)?,
+            task: Task {
+                task_id: row.get(3)?,
+                task_name: row.get(4)?,
+                task: row.get(5)?,
+                status: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                remarks: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                plan_start_time: row.get(8)?,
+                plan_end_time: row.get(9)?,
+                actual_start_time: row.get(10)?,
+                actual_end_time: row.get(11)?,
+                plan_hours: row.get(12)?,
+                actual_hours: row.get(13)?,
+            },
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
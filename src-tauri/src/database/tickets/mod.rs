@@ -0,0 +1,35 @@
+// Tickets module - 从提交信息和日报文本中识别任务编号
+mod tickets_impl;
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use crate::database::types::Task;
+
+pub use tickets_impl::{TrackerPattern, TicketRef, CommitTickets, TicketExtractor};
+
+// Re-export with #[tauri::command] preservation
+#[tauri::command]
+pub fn get_tracker_patterns() -> Vec<TrackerPattern> {
+    tickets_impl::load_tracker_patterns()
+}
+
+#[tauri::command]
+pub fn save_tracker_patterns(patterns: Vec<TrackerPattern>) -> Result<(), String> {
+    tickets_impl::save_tracker_patterns(patterns)
+}
+
+#[tauri::command]
+pub fn extract_ticket_refs(text: String) -> Vec<TicketRef> {
+    tickets_impl::extract_ticket_refs(text)
+}
+
+#[tauri::command]
+pub fn extract_tickets_from_git(repo_path: String, since: String) -> Result<Vec<CommitTickets>, String> {
+    tickets_impl::extract_tickets_from_git(repo_path, since)
+}
+
+#[tauri::command]
+pub fn tasks_by_ticket(start_date: String, end_date: String) -> Result<HashMap<String, Vec<Task>>, String> {
+    tickets_impl::tasks_by_ticket(start_date, end_date)
+}
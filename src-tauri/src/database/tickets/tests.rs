@@ -0,0 +1,46 @@
+// 默认编号规则的识别测试，重点是编号紧挨中文和符号时的边界
+use super::tickets_impl::default_patterns;
+use super::*;
+
+fn keys(text: &str) -> Vec<String> {
+    TicketExtractor::new(default_patterns())
+        .extract(text)
+        .into_iter()
+        .map(|t| t.key)
+        .collect()
+}
+
+#[test]
+fn jira_keys_next_to_chinese() {
+    assert_eq!(keys("修复PROJ-123问题"), vec!["PROJ-123"]);
+    assert_eq!(keys("完成：AB2-7，跟进OPS-45"), vec!["AB2-7", "OPS-45"]);
+}
+
+#[test]
+fn jira_keys_inside_ascii_words_are_ignored() {
+    assert!(keys("xPROJ-123").is_empty());
+    assert!(keys("PROJ-123x").is_empty());
+    assert!(keys("proj-123").is_empty());
+}
+
+#[test]
+fn github_issues_next_to_chinese() {
+    assert_eq!(keys("关闭#42并合并"), vec!["#42"]);
+    assert_eq!(keys("#7 登录页"), vec!["#7"]);
+}
+
+#[test]
+fn github_issues_skip_html_entities_and_words() {
+    assert!(keys("&#39;").is_empty());
+    assert!(keys("a#12").is_empty());
+    assert!(keys("#12a").is_empty());
+}
+
+#[test]
+fn keys_are_deduplicated_in_order() {
+    assert_eq!(keys("OPS-2 修复#3，OPS-2 回归"), vec!["OPS-2", "#3"]);
+    assert_eq!(
+        TicketExtractor::new(default_patterns()).task_id_for("修复PROJ-1和PROJ-2"),
+        Some("PROJ-1,PROJ-2".to_string())
+    );
+}
@@ -0,0 +1,199 @@
+use std::fs;
+use std::collections::HashMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::database::types::Task;
+use crate::database::git_utils::get_commits_since;
+use crate::database::sqlite::{init_db, load_latest_dailies_between};
+use crate::database::excel::utils::extract_tasks_from_daily;
+
+const PATTERNS_FILE: &str = "tracker_patterns.json";
+
+/// 单个问题跟踪系统的编号识别规则
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrackerPattern {
+    pub name: String,                 // 跟踪系统名称，比如 "Jira"、"GitHub"
+    pub pattern: String,              // 正则表达式，第一个捕获组（若有）作为编号
+    pub url_template: Option<String>, // 链接模板，{key} 会被替换为编号，比如 "https://jira.example.com/browse/{key}"
+}
+
+/// 从文本中识别出的一个任务编号
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketRef {
+    pub key: String,
+    pub tracker: String,
+    pub url: Option<String>,
+}
+
+/// 提交记录及其中引用的任务编号
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CommitTickets {
+    pub hash: String,
+    pub time: String,
+    pub message: String,
+    pub tickets: Vec<TicketRef>,
+}
+
+// 中文在正则里也算单词字符，编号常紧挨着中文（如"修复PROJ-123问题"），所以只按ASCII判断边界
+pub(super) fn default_patterns() -> Vec<TrackerPattern> {
+    vec![
+        TrackerPattern {
+            name: "Jira".to_string(),
+            pattern: r"(?-u:\b)([A-Z][A-Z0-9]+-\d+)(?-u:\b)".to_string(),
+            url_template: None,
+        },
+        TrackerPattern {
+            name: "GitHub".to_string(),
+            pattern: r"(?:^|[^A-Za-z0-9_&])(#\d+)(?-u:\b)".to_string(),
+            url_template: None,
+        },
+    ]
+}
+
+pub fn load_tracker_patterns() -> Vec<TrackerPattern> {
+    match fs::read_to_string(PATTERNS_FILE) {
+        Ok(json) => match serde_json::from_str(&json) {
+            Ok(patterns) => patterns,
+            Err(e) => {
+                eprintln!("解析任务编号规则失败，使用默认规则: {}", e);
+                default_patterns()
+            }
+        },
+        Err(_) => default_patterns(),
+    }
+}
+
+pub fn save_tracker_patterns(patterns: Vec<TrackerPattern>) -> Result<(), String> {
+    // 保存前先校验所有正则表达式，避免写入无法使用的配置
+    for pattern in &patterns {
+        Regex::new(&pattern.pattern)
+            .map_err(|e| format!("规则 {} 的正则表达式无效: {}", pattern.name, e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&patterns)
+        .map_err(|e| format!("序列化任务编号规则失败: {}", e))?;
+    fs::write(PATTERNS_FILE, json)
+        .map_err(|e| format!("保存任务编号规则失败: {}", e))
+}
+
+/// 预编译的编号识别器，批量处理时避免重复编译正则
+pub struct TicketExtractor {
+    rules: Vec<(TrackerPattern, Regex)>,
+}
+
+impl TicketExtractor {
+    pub fn new(patterns: Vec<TrackerPattern>) -> Self {
+        let rules = patterns
+            .into_iter()
+            .filter_map(|pattern| match Regex::new(&pattern.pattern) {
+                Ok(re) => Some((pattern, re)),
+                Err(e) => {
+                    eprintln!("忽略无效的任务编号规则 {}: {}", pattern.name, e);
+                    None
+                }
+            })
+            .collect();
+        TicketExtractor { rules }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(load_tracker_patterns())
+    }
+
+    /// 按出现顺序提取文本中的所有编号，重复的编号只保留一次
+    pub fn extract(&self, text: &str) -> Vec<TicketRef> {
+        let mut found: Vec<(usize, TicketRef)> = Vec::new();
+
+        for (pattern, re) in &self.rules {
+            for caps in re.captures_iter(text) {
+                let m = match caps.get(1).or_else(|| caps.get(0)) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let key = m.as_str().to_string();
+                if found.iter().any(|(_, t)| t.key == key) {
+                    continue;
+                }
+                let url = pattern
+                    .url_template
+                    .as_ref()
+                    .map(|tpl| tpl.replace("{key}", key.trim_start_matches('#')));
+                found.push((m.start(), TicketRef {
+                    key,
+                    tracker: pattern.name.clone(),
+                    url,
+                }));
+            }
+        }
+
+        found.sort_by_key(|(pos, _)| *pos);
+        found.into_iter().map(|(_, t)| t).collect()
+    }
+
+    /// 提取文本中的编号并拼接为 task_id 字段的格式（多个编号用逗号分隔）
+    pub fn task_id_for(&self, text: &str) -> Option<String> {
+        let keys: Vec<String> = self.extract(text).into_iter().map(|t| t.key).collect();
+        if keys.is_empty() {
+            None
+        } else {
+            Some(keys.join(","))
+        }
+    }
+
+    /// 根据编号查找对应的链接
+    pub fn url_for(&self, key: &str) -> Option<String> {
+        self.extract(key).into_iter().find(|t| t.key == key).and_then(|t| t.url)
+    }
+
+    /// 为没有编号的任务从任务内容中补全 task_id
+    pub fn fill_task_ids(&self, tasks: &mut [Task]) {
+        for task in tasks.iter_mut() {
+            if task.task_id.as_deref().unwrap_or_default().trim().is_empty() {
+                task.task_id = self.task_id_for(&task.task);
+            }
+        }
+    }
+}
+
+pub fn extract_ticket_refs(text: String) -> Vec<TicketRef> {
+    TicketExtractor::from_config().extract(&text)
+}
+
+pub fn extract_tickets_from_git(repo_path: String, since: String) -> Result<Vec<CommitTickets>, String> {
+    let extractor = TicketExtractor::from_config();
+    let commits = get_commits_since(&repo_path, &since)?;
+
+    Ok(commits
+        .into_iter()
+        .filter_map(|commit| {
+            let tickets = extractor.extract(&commit.message);
+            if tickets.is_empty() {
+                return None;
+            }
+            Some(CommitTickets {
+                hash: commit.hash,
+                time: commit.time,
+                message: commit.message,
+                tickets,
+            })
+        })
+        .collect())
+}
+
+/// 按任务编号汇总指定日期范围内日报中的任务
+pub fn tasks_by_ticket(start_date: String, end_date: String) -> Result<HashMap<String, Vec<Task>>, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    let daily_reports = load_latest_dailies_between(&conn, &start_date, &end_date, None)?;
+    let tasks = extract_tasks_from_daily(&daily_reports)?;
+
+    let mut grouped: HashMap<String, Vec<Task>> = HashMap::new();
+    for task in tasks {
+        if let Some(task_id) = &task.task_id {
+            for key in task_id.split(',').map(|k| k.trim()).filter(|k| !k.is_empty()) {
+                grouped.entry(key.to_string()).or_default().push(task.clone());
+            }
+        }
+    }
+
+    Ok(grouped)
+}
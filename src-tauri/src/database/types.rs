@@ -21,6 +21,36 @@ pub struct ReportTemplate {
     pub tasks: Vec<Task>,
     pub next_week_plan: String,
     pub column_indices: HashMap<String, usize>,
+    #[serde(default)]
+    pub warnings: Vec<ImportWarning>, // 导入时无法识别的单元格
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImportWarning {
+    pub row: usize,       // Excel中的行号，从1开始
+    pub field: String,
+    pub value: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImportColumn {
+    pub field: String,
+    pub column: String,   // 列标，如 "C"
+    pub header: String,   // 表头文字，没有表头时为空
+    pub source: String,   // detected: 按表头识别，mapped: 手动指定，default: 默认的前三列
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ImportPreview {
+    pub sheet: String,
+    pub header_row: Option<u32>,  // Excel中的行号，没有表头时为空
+    pub start_row: u32,           // 第一行数据的行号
+    pub columns: Vec<ImportColumn>,
+    pub unmapped: Vec<(String, String)>, // 没有对应字段的列：(列标, 表头文字)
+    pub total_rows: usize,
+    pub sample_rows: Vec<Task>,
+    pub warnings: Vec<ImportWarning>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -38,7 +68,7 @@ pub struct DailyReport {
     pub remarks: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct UserInfo {
     pub position: String,
     pub department: String,
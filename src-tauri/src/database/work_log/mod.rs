@@ -0,0 +1,30 @@
+// Work log module - 后台记录Git提交与分支切换
+mod work_log_impl;
+
+pub use work_log_impl::{WatchedRepo, WorkLogEntry, start_work_log_watcher};
+
+// Re-export with #[tauri::command] preservation
+#[tauri::command]
+pub fn get_watched_repos() -> Vec<WatchedRepo> {
+    work_log_impl::load_watched_repos()
+}
+
+#[tauri::command]
+pub fn save_watched_repos(repos: Vec<WatchedRepo>) -> Result<(), String> {
+    work_log_impl::save_watched_repos(repos)
+}
+
+#[tauri::command]
+pub fn scan_work_log_now() -> Result<usize, String> {
+    work_log_impl::scan_all_repos()
+}
+
+#[tauri::command]
+pub fn get_work_log(date: String) -> Result<Vec<WorkLogEntry>, String> {
+    work_log_impl::get_work_log(&date)
+}
+
+#[tauri::command]
+pub fn get_daily_timeline(date: String) -> Result<String, String> {
+    work_log_impl::get_daily_timeline(&date)
+}
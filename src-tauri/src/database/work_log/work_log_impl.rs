@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use std::thread;
+use chrono::{Local, TimeZone};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::database::sqlite::init_db;
+
+const REPOS_FILE: &str = "work_log_repos.json";
+const POLL_INTERVAL_SECS: u64 = 30;
+// 每次扫描读取的reflog条数上限，足够覆盖两次轮询之间的操作
+const REFLOG_LIMIT: &str = "200";
+
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 需要后台记录的仓库
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WatchedRepo {
+    pub path: String,
+    pub enabled: bool,
+}
+
+/// 工作记录条目
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorkLogEntry {
+    pub id: Option<i64>,
+    pub repo_path: String,
+    pub kind: String,        // commit / checkout / merge
+    pub ref_name: Option<String>,    // 切换分支时为目标分支
+    pub commit_hash: String,
+    pub message: String,
+    pub occurred_at: String, // 本地时间，RFC 3339格式
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS work_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repo_path TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            ref_name TEXT,
+            commit_hash TEXT NOT NULL,
+            message TEXT,
+            occurred_at TEXT NOT NULL,
+            recorded_at TEXT NOT NULL,
+            UNIQUE(repo_path, kind, commit_hash, occurred_at)
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+pub fn load_watched_repos() -> Vec<WatchedRepo> {
+    fs::read_to_string(REPOS_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_watched_repos(repos: Vec<WatchedRepo>) -> Result<(), String> {
+    for repo in &repos {
+        if !Path::new(&repo.path).exists() {
+            return Err(format!("路径不存在: {}", repo.path));
+        }
+    }
+    let json = serde_json::to_string_pretty(&repos)
+        .map_err(|e| format!("序列化仓库列表失败: {}", e))?;
+    fs::write(REPOS_FILE, json).map_err(|e| format!("保存仓库列表失败: {}", e))
+}
+
+// 解析一行reflog输出：hash \x1f HEAD@{unix时间} \x1f reflog说明 \x1f 提交标题
+fn parse_reflog_line(repo_path: &str, line: &str) -> Option<WorkLogEntry> {
+    let fields: Vec<&str> = line.splitn(4, '\x1f').collect();
+    if fields.len() < 4 {
+        return None;
+    }
+
+    let timestamp: i64 = fields[1]
+        .split('{')
+        .nth(1)?
+        .trim_end_matches('}')
+        .parse()
+        .ok()?;
+    let occurred_at = Local.timestamp_opt(timestamp, 0).single()?.to_rfc3339();
+
+    let (action, detail) = fields[2].split_once(": ").unwrap_or((fields[2], ""));
+    let (kind, ref_name, message) = if action.starts_with("checkout") {
+        // "moving from main to feature/x"
+        let target = detail.rsplit(" to ").next().map(|s| s.to_string());
+        ("checkout", target, detail.to_string())
+    } else if action.starts_with("commit") || action.starts_with("cherry-pick") {
+        ("commit", None, fields[3].to_string())
+    } else if action.starts_with("merge") || action.starts_with("pull") {
+        ("merge", None, fields[2].to_string())
+    } else {
+        return None;
+    };
+
+    Some(WorkLogEntry {
+        id: None,
+        repo_path: repo_path.to_string(),
+        kind: kind.to_string(),
+        ref_name,
+        commit_hash: fields[0].to_string(),
+        message,
+        occurred_at,
+    })
+}
+
+/// 读取仓库的HEAD reflog，把新的提交与分支切换写入work_log表
+///
+/// reflog本身带有时间戳，因此即使应用在操作发生时没有运行，下次扫描也能补录
+pub fn scan_repo(conn: &Connection, repo_path: &str) -> Result<usize, String> {
+    let output = Command::new("git")
+        .args([
+            "-C",
+            repo_path,
+            "reflog",
+            "show",
+            "-n",
+            REFLOG_LIMIT,
+            "--date=unix",
+            "--format=%H%x1f%gd%x1f%gs%x1f%s",
+            "HEAD",
+        ])
+        .output()
+        .map_err(|e| format!("执行Git命令失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("读取reflog失败, 退出码: {}", output.status));
+    }
+
+    let recorded_at = Local::now().to_rfc3339();
+    let mut inserted = 0;
+    // reflog按时间倒序输出，反转后插入，保证同一秒内的操作按发生顺序排列
+    for line in String::from_utf8_lossy(&output.stdout).lines().rev() {
+        if let Some(entry) = parse_reflog_line(repo_path, line) {
+            inserted += conn.execute(
+                "INSERT OR IGNORE INTO work_log (repo_path, kind, ref_name, commit_hash, message, occurred_at, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    entry.repo_path,
+                    entry.kind,
+                    entry.ref_name,
+                    entry.commit_hash,
+                    entry.message,
+                    entry.occurred_at,
+                    recorded_at,
+                ],
+            ).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(inserted)
+}
+
+// 仓库的Git目录，worktree和子模块中 .git 是指向真实目录的文件，由Git解析
+fn resolve_git_dir(repo_path: &str) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["-C", repo_path, "rev-parse", "--git-dir"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let git_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    // 在仓库根目录执行时返回的是相对路径
+    Some(if git_dir.is_absolute() { git_dir } else { Path::new(repo_path).join(git_dir) })
+}
+
+// reflog文件的修改时间，没有变化时跳过本轮扫描
+fn reflog_mtime(git_dir: &Path) -> Option<SystemTime> {
+    fs::metadata(git_dir.join("logs").join("HEAD"))
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+pub fn scan_all_repos() -> Result<usize, String> {
+    let conn = open_db()?;
+    let mut total = 0;
+    for repo in load_watched_repos().into_iter().filter(|r| r.enabled) {
+        match scan_repo(&conn, &repo.path) {
+            Ok(count) => total += count,
+            Err(e) => eprintln!("扫描仓库 {} 失败: {}", repo.path, e),
+        }
+    }
+    Ok(total)
+}
+
+/// 启动后台记录线程，重复调用只会启动一次
+pub fn start_work_log_watcher() {
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(|| {
+        let mut last_seen: HashMap<String, SystemTime> = HashMap::new();
+        // 仓库路径对应的Git目录，只在第一次扫描时解析
+        let mut git_dirs: HashMap<String, PathBuf> = HashMap::new();
+        loop {
+            match open_db() {
+                Ok(conn) => {
+                    for repo in load_watched_repos().into_iter().filter(|r| r.enabled) {
+                        if !git_dirs.contains_key(&repo.path) {
+                            if let Some(git_dir) = resolve_git_dir(&repo.path) {
+                                git_dirs.insert(repo.path.clone(), git_dir);
+                            }
+                        }
+                        let mtime = git_dirs.get(&repo.path).and_then(|dir| reflog_mtime(dir));
+                        if mtime.is_some() && last_seen.get(&repo.path) == mtime.as_ref() {
+                            continue;
+                        }
+                        match scan_repo(&conn, &repo.path) {
+                            Ok(count) => {
+                                if count > 0 {
+                                    eprintln!("仓库 {} 新增 {} 条工作记录", repo.path, count);
+                                }
+                                if let Some(mtime) = mtime {
+                                    last_seen.insert(repo.path.clone(), mtime);
+                                }
+                            },
+                            Err(e) => eprintln!("扫描仓库 {} 失败: {}", repo.path, e),
+                        }
+                    }
+                },
+                Err(e) => eprintln!("打开工作记录数据库失败: {}", e),
+            }
+            thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
+
+/// 查询某一天（YYYY-MM-DD）的工作记录，按时间正序
+pub fn get_work_log(date: &str) -> Result<Vec<WorkLogEntry>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, repo_path, kind, ref_name, commit_hash, message, occurred_at FROM work_log
+         WHERE occurred_at LIKE ?1 ORDER BY occurred_at ASC, id ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([format!("{}%", date)], |row| {
+        Ok(WorkLogEntry {
+            id: row.get(0)?,
+            repo_path: row.get(1)?,
+            kind: row.get(2)?,
+            ref_name: row.get(3)?,
+            commit_hash: row.get(4)?,
+            message: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            occurred_at: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// 把某一天的工作记录格式化为时间线，比如"10:12 切换到 feature/x"
+pub fn get_daily_timeline(date: &str) -> Result<String, String> {
+    let entries = get_work_log(date)?;
+    let multi_repo = entries
+        .iter()
+        .map(|e| e.repo_path.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len() > 1;
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let time = entry.occurred_at.get(11..16).unwrap_or_default();
+            let action = match entry.kind.as_str() {
+                "checkout" => format!("切换到 {}", entry.ref_name.clone().unwrap_or_default()),
+                "commit" => format!("提交 {}", entry.message),
+                _ => format!("合并 {}", entry.message),
+            };
+            if multi_repo {
+                let repo_name = Path::new(&entry.repo_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| entry.repo_path.clone());
+                format!("{} [{}] {}", time, repo_name, action)
+            } else {
+                format!("{} {}", time, action)
+            }
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
@@ -1,19 +1,244 @@
+use std::fmt;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, Mac};
 use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 
-#[tauri::command]
-pub fn send_to_dingtalk(token: String, content: String) -> Result<(), String> {
-    let url = format!("https://oapi.dingtalk.com/robot/send?access_token={}", token);
-    let client = Client::new();
-    let res = client.post(&url)
-        .json(&serde_json::json!({
-            "msgtype": "text",
-            "text": { "content": content }
-        }))
-        .send()
-        .map_err(|e| e.to_string())?;
-    if res.status().is_success() {
-        Ok(())
-    } else {
-        Err("Failed to send message".into())
+const DINGTALK_ENDPOINT: &str = "https://oapi.dingtalk.com/robot/send";
+
+/// @ 提醒设置，可按手机号或用户ID提醒，也可以@所有人
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DingTalkAt {
+    #[serde(default)]
+    pub at_mobiles: Vec<String>,
+    #[serde(default)]
+    pub at_user_ids: Vec<String>,
+    #[serde(default)]
+    pub is_at_all: bool,
+}
+
+/// actionCard 独立跳转按钮
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActionCardButton {
+    pub title: String,
+    pub action_url: String,
+}
+
+/// 钉钉机器人支持的消息类型
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "msgtype", rename_all = "camelCase")]
+pub enum DingTalkMessage {
+    Text {
+        content: String,
+    },
+    Markdown {
+        title: String,
+        text: String,
+    },
+    Link {
+        title: String,
+        text: String,
+        message_url: String,
+        pic_url: Option<String>,
+    },
+    ActionCard {
+        title: String,
+        text: String,
+        // 整体跳转时填写 single_title/single_url，否则使用 btns 独立跳转
+        single_title: Option<String>,
+        single_url: Option<String>,
+        #[serde(default)]
+        btns: Vec<ActionCardButton>,
+        #[serde(default)]
+        btn_vertical: bool,
+    },
+}
+
+impl DingTalkMessage {
+    // 转换为钉钉接口要求的请求体
+    fn to_payload(&self, at: Option<&DingTalkAt>) -> serde_json::Value {
+        let mut payload = match self {
+            DingTalkMessage::Text { content } => serde_json::json!({
+                "msgtype": "text",
+                "text": { "content": content }
+            }),
+            DingTalkMessage::Markdown { title, text } => serde_json::json!({
+                "msgtype": "markdown",
+                "markdown": { "title": title, "text": text }
+            }),
+            DingTalkMessage::Link { title, text, message_url, pic_url } => serde_json::json!({
+                "msgtype": "link",
+                "link": {
+                    "title": title,
+                    "text": text,
+                    "messageUrl": message_url,
+                    "picUrl": pic_url.clone().unwrap_or_default()
+                }
+            }),
+            DingTalkMessage::ActionCard { title, text, single_title, single_url, btns, btn_vertical } => {
+                let mut card = serde_json::json!({
+                    "title": title,
+                    "text": text,
+                    "btnOrientation": if *btn_vertical { "0" } else { "1" }
+                });
+                if let (Some(single_title), Some(single_url)) = (single_title, single_url) {
+                    card["singleTitle"] = serde_json::json!(single_title);
+                    card["singleURL"] = serde_json::json!(single_url);
+                } else {
+                    card["btns"] = serde_json::json!(btns
+                        .iter()
+                        .map(|b| serde_json::json!({ "title": b.title, "actionURL": b.action_url }))
+                        .collect::<Vec<_>>());
+                }
+                serde_json::json!({ "msgtype": "actionCard", "actionCard": card })
+            }
+        };
+
+        if let Some(at) = at {
+            payload["at"] = serde_json::json!({
+                "atMobiles": at.at_mobiles,
+                "atUserIds": at.at_user_ids,
+                "isAtAll": at.is_at_all
+            });
+        }
+        payload
+    }
+}
+
+/// 钉钉发送失败的具体原因
+#[derive(Debug)]
+pub enum DingTalkError {
+    Network(String),
+    Http(u16),
+    InvalidResponse(String),
+    // 300001: access_token 不存在或已失效
+    InvalidToken(String),
+    // 310000: 关键词、加签或IP白名单校验未通过
+    SecurityCheckFailed(String),
+    // 130101/410100: 发送过于频繁
+    RateLimited(String),
+    Api { errcode: i64, errmsg: String },
+}
+
+impl fmt::Display for DingTalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DingTalkError::Network(e) => write!(f, "钉钉网络请求失败: {}", e),
+            DingTalkError::Http(status) => write!(f, "钉钉接口返回HTTP状态码 {}", status),
+            DingTalkError::InvalidResponse(e) => write!(f, "无法解析钉钉返回内容: {}", e),
+            DingTalkError::InvalidToken(msg) => write!(f, "机器人access_token无效: {}", msg),
+            DingTalkError::SecurityCheckFailed(msg) => {
+                write!(f, "安全设置校验失败（请检查加签密钥、关键词或IP白名单）: {}", msg)
+            }
+            DingTalkError::RateLimited(msg) => write!(f, "发送过于频繁，请稍后再试: {}", msg),
+            DingTalkError::Api { errcode, errmsg } => write!(f, "钉钉返回错误 {}: {}", errcode, errmsg),
+        }
     }
-} 
\ No newline at end of file
+}
+
+impl std::error::Error for DingTalkError {}
+
+#[derive(Deserialize)]
+struct DingTalkResponse {
+    errcode: i64,
+    #[serde(default)]
+    errmsg: String,
+}
+
+impl DingTalkError {
+    fn from_response(errcode: i64, errmsg: String) -> Self {
+        match errcode {
+            300001 => DingTalkError::InvalidToken(errmsg),
+            310000 => DingTalkError::SecurityCheckFailed(errmsg),
+            130101 | 410100 => DingTalkError::RateLimited(errmsg),
+            _ => DingTalkError::Api { errcode, errmsg },
+        }
+    }
+}
+
+/// 计算加签模式下的签名：HmacSHA256("{timestamp}\n{secret}")，Base64后再做URL编码
+pub fn sign(secret: &str, timestamp_ms: i64) -> String {
+    let string_to_sign = format!("{}\n{}", timestamp_ms, secret);
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC可以接受任意长度的密钥");
+    mac.update(string_to_sign.as_bytes());
+    let signature = BASE64.encode(mac.finalize().into_bytes());
+    urlencoding::encode(&signature).into_owned()
+}
+
+/// 群机器人
+pub struct DingTalkRobot {
+    webhook: String,
+    secret: Option<String>,
+}
+
+impl DingTalkRobot {
+    /// `token` 可以是 access_token，也可以是完整的 Webhook 地址
+    pub fn new(token: &str, secret: Option<String>) -> Self {
+        let webhook = if token.starts_with("http://") || token.starts_with("https://") {
+            token.to_string()
+        } else {
+            format!("{}?access_token={}", DINGTALK_ENDPOINT, token)
+        };
+        DingTalkRobot {
+            webhook,
+            secret: secret.filter(|s| !s.trim().is_empty()),
+        }
+    }
+
+    fn request_url(&self) -> String {
+        match &self.secret {
+            Some(secret) => {
+                let timestamp = chrono::Utc::now().timestamp_millis();
+                format!("{}&timestamp={}&sign={}", self.webhook, timestamp, sign(secret, timestamp))
+            }
+            None => self.webhook.clone(),
+        }
+    }
+
+    pub fn send(&self, message: &DingTalkMessage, at: Option<&DingTalkAt>) -> Result<(), DingTalkError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| DingTalkError::Network(e.to_string()))?;
+        let res = client
+            .post(self.request_url())
+            .json(&message.to_payload(at))
+            .send()
+            .map_err(|e| DingTalkError::Network(e.to_string()))?;
+
+        if !res.status().is_success() {
+            return Err(DingTalkError::Http(res.status().as_u16()));
+        }
+
+        let body: DingTalkResponse = res
+            .json()
+            .map_err(|e| DingTalkError::InvalidResponse(e.to_string()))?;
+        if body.errcode == 0 {
+            Ok(())
+        } else {
+            Err(DingTalkError::from_response(body.errcode, body.errmsg))
+        }
+    }
+}
+
+#[tauri::command]
+pub fn send_to_dingtalk(token: String, content: String, secret: Option<String>) -> Result<(), String> {
+    DingTalkRobot::new(&token, secret)
+        .send(&DingTalkMessage::Text { content }, None)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn send_dingtalk_message(
+    token: String,
+    secret: Option<String>,
+    message: DingTalkMessage,
+    at: Option<DingTalkAt>,
+) -> Result<(), String> {
+    DingTalkRobot::new(&token, secret)
+        .send(&message, at.as_ref())
+        .map_err(|e| e.to_string())
+}
@@ -2,6 +2,10 @@ mod database;
 mod idle;
 mod dingtalk;
 mod notify;
+mod notifier;
+mod auto_submit;
+
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -10,6 +14,22 @@ pub fn run() {
       crate::database::save_daily,
       crate::idle::start_idle_detection,
       crate::dingtalk::send_to_dingtalk,
+      crate::dingtalk::send_dingtalk_message,
+      crate::notifier::list_channels,
+      crate::notifier::save_channel,
+      crate::notifier::delete_channel,
+      crate::notifier::test_channel,
+      crate::notifier::send_report,
+      crate::auto_submit::get_auto_submit_config,
+      crate::auto_submit::save_auto_submit_config,
+      crate::auto_submit::run_auto_submit_now,
+      crate::auto_submit::get_send_history,
+      crate::notify::list_reminder_rules,
+      crate::notify::save_reminder_rule,
+      crate::notify::delete_reminder_rule,
+      crate::notify::snooze_reminder,
+      crate::notify::dismiss_reminder,
+      crate::notify::get_reminder_states,
       crate::database::excel::parse_excel_template,
       crate::database::excel::generate_excel_report,
       crate::database::excel::save_report_template,
@@ -18,10 +38,38 @@ pub fn run() {
       crate::database::excel::export_with_template,
       crate::database::excel::import_excel_tasks,
       crate::database::excel::generate_weekly_from_daily,
+      crate::database::excel::load_template_mapping,
+      crate::database::excel::save_template_mapping,
+      crate::database::excel::validate_template_mapping,
+      crate::database::excel::detect_excel_headers,
+      crate::database::excel::get_header_synonyms,
+      crate::database::excel::save_header_synonyms,
+      crate::database::excel::list_excel_sheets,
+      crate::database::excel::preview_excel_import,
+      crate::database::export::export_weekly_docx,
+      crate::database::export::export_monthly_docx,
+      crate::database::export::export_weekly_pdf,
+      crate::database::export::export_monthly_pdf,
+      crate::database::task_import::import_tasks_to_db,
+      crate::database::task_import::list_import_batches,
+      crate::database::task_import::undo_import_batch,
+      crate::database::task_import::list_planned_tasks,
       crate::database::report::auto_generate_weekly,
+      crate::database::report::generate_weekly_report,
+      crate::database::report::render_report_html,
       crate::database::report::save_template,
+      crate::database::report::validate_template,
       crate::database::report::read_template, 
       crate::database::report::render_daily_with_template,
+      crate::database::report::list_templates,
+      crate::database::report::get_named_template,
+      crate::database::report::save_named_template,
+      crate::database::report::delete_named_template,
+      crate::database::report::list_template_partials,
+      crate::database::report::save_template_partial,
+      crate::database::report::delete_template_partial,
+      crate::database::report::preview_template,
+      crate::database::report::get_sample_context,
       crate::database::file_utils::read_text_file,
       crate::database::file_utils::select_file,
       crate::database::file_utils::select_save_path,
@@ -29,11 +77,41 @@ pub fn run() {
       crate::database::supabase::get_recent_daily_reports,
       crate::database::supabase::get_daily_report_by_date,
       crate::database::supabase::test_supabase_connection,
+      crate::database::tickets::get_tracker_patterns,
+      crate::database::tickets::save_tracker_patterns,
+      crate::database::tickets::extract_ticket_refs,
+      crate::database::tickets::extract_tickets_from_git,
+      crate::database::tickets::tasks_by_ticket,
+      crate::database::work_log::get_watched_repos,
+      crate::database::work_log::save_watched_repos,
+      crate::database::work_log::scan_work_log_now,
+      crate::database::work_log::get_work_log,
+      crate::database::work_log::get_daily_timeline,
+      crate::database::scheduler::list_scheduled_jobs,
+      crate::database::scheduler::save_scheduled_job,
+      crate::database::scheduler::delete_scheduled_job,
+      crate::database::scheduler::run_scheduled_job_now,
+      crate::database::scheduler::list_upcoming_runs,
+      crate::database::calendar::list_calendar_days,
+      crate::database::calendar::get_holiday_data,
+      crate::database::calendar::save_holiday_data,
+      crate::database::calendar::import_holiday_ics,
+      crate::database::calendar::list_personal_leave,
+      crate::database::calendar::add_personal_leave,
+      crate::database::calendar::remove_personal_leave,
+      crate::database::coverage::get_report_coverage,
+      crate::database::coverage::get_team_report_coverage,
     ])
     .plugin(tauri_plugin_notification::init())
     .plugin(tauri_plugin_dialog::init())
     .setup(|app| {
-      crate::database::report::start_weekly_timer();
+      if let Ok(dir) = app.path().app_data_dir() {
+        crate::database::report::set_template_dir(dir.join("templates"));
+      }
+      crate::database::work_log::start_work_log_watcher();
+      crate::auto_submit::register_auto_submit_job();
+      crate::notify::register_reminder_job(app.handle().clone());
+      crate::database::scheduler::start_scheduler();
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
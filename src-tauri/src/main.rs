@@ -3,7 +3,13 @@
 // #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod database;
+mod dingtalk;
+mod notifier;
+mod auto_submit;
+mod notify;
 use database::*;
+use database::report::set_template_dir;
+use tauri::Manager;
 
 // 添加日志记录的初始化
 fn setup_logging() {
@@ -79,7 +85,7 @@ async fn main() {
     setup_logging();
     eprintln!("应用程序开始初始化");
     
-    start_weekly_timer();
+    start_work_log_watcher();
 
     // 初始化Supabase连接，确保数据库结构正确
     match database::supabase::init_supabase().await {
@@ -89,25 +95,64 @@ async fn main() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            // 命名模板和片段保存在应用数据目录，需在调度器执行任务前设置
+            if let Ok(dir) = app.path().app_data_dir() {
+                set_template_dir(dir.join("templates"));
+            }
+            auto_submit::register_auto_submit_job();
+            notify::register_reminder_job(app.handle().clone());
+            start_scheduler();
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             log_message,
             save_daily,
             auto_generate_weekly,
+            generate_weekly_report,
+            render_report_html,
             read_text_file,
             save_template,
+            validate_template,
             read_template,
             render_daily_with_template,
+            list_templates,
+            get_named_template,
+            save_named_template,
+            delete_named_template,
+            list_template_partials,
+            save_template_partial,
+            delete_template_partial,
+            preview_template,
+            get_sample_context,
             parse_excel_template,
             generate_excel_report,
             save_report_template,
             load_report_template,
             import_excel_tasks,
+            load_template_mapping,
+            save_template_mapping,
+            validate_template_mapping,
+            detect_excel_headers,
+            get_header_synonyms,
+            save_header_synonyms,
+            list_excel_sheets,
+            preview_excel_import,
             save_daily_report,
             get_recent_daily_reports,
             get_recent_daily_reports_by_user,
             get_daily_report_by_date,
             generate_weekly_from_daily,
+            export_weekly_docx,
+            export_monthly_docx,
+            export_weekly_pdf,
+            export_monthly_pdf,
+            import_tasks_to_db,
+            list_import_batches,
+            undo_import_batch,
+            list_planned_tasks,
             export_with_template,
             select_file,
             select_save_path,
@@ -117,6 +162,47 @@ async fn main() {
             main_add_user_id_column,
             get_git_changes,
             get_git_last_commit,
+            get_tracker_patterns,
+            save_tracker_patterns,
+            extract_ticket_refs,
+            extract_tickets_from_git,
+            tasks_by_ticket,
+            get_watched_repos,
+            save_watched_repos,
+            scan_work_log_now,
+            get_work_log,
+            get_daily_timeline,
+            list_scheduled_jobs,
+            save_scheduled_job,
+            delete_scheduled_job,
+            run_scheduled_job_now,
+            list_upcoming_runs,
+            list_calendar_days,
+            get_holiday_data,
+            save_holiday_data,
+            import_holiday_ics,
+            list_personal_leave,
+            add_personal_leave,
+            remove_personal_leave,
+            get_report_coverage,
+            get_team_report_coverage,
+            dingtalk::send_to_dingtalk,
+            dingtalk::send_dingtalk_message,
+            notifier::list_channels,
+            notifier::save_channel,
+            notifier::delete_channel,
+            notifier::test_channel,
+            notifier::send_report,
+            auto_submit::get_auto_submit_config,
+            auto_submit::save_auto_submit_config,
+            auto_submit::run_auto_submit_now,
+            auto_submit::get_send_history,
+            notify::list_reminder_rules,
+            notify::save_reminder_rule,
+            notify::delete_reminder_rule,
+            notify::snooze_reminder,
+            notify::dismiss_reminder,
+            notify::get_reminder_states,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,99 @@
+use std::fs;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+const CHANNELS_FILE: &str = "notify_channels.json";
+
+/// SMTP邮件渠道配置
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EmailSettings {
+    pub host: String,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    // tls / starttls / none，none 只用于本地调试
+    #[serde(default = "default_security")]
+    pub security: String,
+}
+
+fn default_security() -> String {
+    "starttls".to_string()
+}
+
+/// 各渠道的连接参数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelSettings {
+    DingTalk {
+        token: String, // access_token 或完整 Webhook 地址
+        secret: Option<String>,
+    },
+    WeCom {
+        webhook_url: String,
+    },
+    Feishu {
+        webhook_url: String,
+        secret: Option<String>,
+    },
+    Slack {
+        webhook_url: String,
+    },
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    Email(EmailSettings),
+}
+
+/// 外发渠道，按团队分组管理
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelConfig {
+    pub id: String,
+    pub name: String,
+    pub team: String,
+    pub enabled: bool,
+    pub settings: ChannelSettings,
+}
+
+/// 读取渠道配置，文件不存在时视为没有渠道
+///
+/// 解析失败时返回错误而不是空列表，避免保存渠道时用空列表覆盖手动修改过的配置
+pub fn load_channels() -> Result<Vec<ChannelConfig>, String> {
+    match fs::read_to_string(CHANNELS_FILE) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("解析渠道配置 {} 失败: {}", CHANNELS_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("读取渠道配置失败: {}", e)),
+    }
+}
+
+fn write_channels(channels: &[ChannelConfig]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(channels)
+        .map_err(|e| format!("序列化渠道配置失败: {}", e))?;
+    fs::write(CHANNELS_FILE, json).map_err(|e| format!("保存渠道配置失败: {}", e))
+}
+
+/// 新增或更新渠道（按id匹配）
+pub fn save_channel(channel: ChannelConfig) -> Result<(), String> {
+    if channel.id.trim().is_empty() {
+        return Err("渠道ID不能为空".to_string());
+    }
+    let mut channels = load_channels()?;
+    match channels.iter_mut().find(|c| c.id == channel.id) {
+        Some(existing) => *existing = channel,
+        None => channels.push(channel),
+    }
+    write_channels(&channels)
+}
+
+pub fn delete_channel(id: &str) -> Result<(), String> {
+    let mut channels = load_channels()?;
+    let before = channels.len();
+    channels.retain(|c| c.id != id);
+    if channels.len() == before {
+        return Err(format!("找不到渠道: {}", id));
+    }
+    write_channels(&channels)
+}
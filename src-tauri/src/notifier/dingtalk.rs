@@ -0,0 +1,24 @@
+use crate::dingtalk::{DingTalkMessage, DingTalkRobot};
+use super::{Notifier, OutgoingReport};
+
+pub struct DingTalkNotifier {
+    robot: DingTalkRobot,
+}
+
+impl DingTalkNotifier {
+    pub fn new(token: &str, secret: Option<String>) -> Self {
+        DingTalkNotifier {
+            robot: DingTalkRobot::new(token, secret),
+        }
+    }
+}
+
+impl Notifier for DingTalkNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        let message = DingTalkMessage::Markdown {
+            title: report.title.clone(),
+            text: format!("### {}\n\n{}", report.title, report.markdown),
+        };
+        self.robot.send(&message, None).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,71 @@
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use super::config::EmailSettings;
+use super::{Notifier, OutgoingReport};
+
+pub struct EmailNotifier {
+    settings: EmailSettings,
+}
+
+impl EmailNotifier {
+    pub fn new(settings: EmailSettings) -> Self {
+        EmailNotifier { settings }
+    }
+
+    fn transport(&self) -> Result<SmtpTransport, String> {
+        let settings = &self.settings;
+        let mut builder = match settings.security.as_str() {
+            "tls" => SmtpTransport::relay(&settings.host),
+            "none" => Ok(SmtpTransport::builder_dangerous(&settings.host)),
+            _ => SmtpTransport::starttls_relay(&settings.host),
+        }
+        .map_err(|e| format!("SMTP配置无效: {}", e))?;
+
+        if let Some(port) = settings.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(builder.build())
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        if self.settings.to.is_empty() {
+            return Err("未配置收件人".to_string());
+        }
+
+        let from: Mailbox = self.settings.from.parse()
+            .map_err(|e| format!("发件人地址无效: {}", e))?;
+        let mut builder = Message::builder().from(from).subject(report.title.clone());
+        for to in &self.settings.to {
+            let mailbox: Mailbox = to.parse().map_err(|e| format!("收件人地址无效 {}: {}", to, e))?;
+            builder = builder.to(mailbox);
+        }
+        let message = if report.attachments.is_empty() {
+            builder
+                .header(ContentType::TEXT_PLAIN)
+                .body(report.markdown.clone())
+        } else {
+            let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(report.markdown.clone()));
+            for attachment in &report.attachments {
+                let content_type = ContentType::parse(&attachment.content_type)
+                    .map_err(|e| format!("附件类型无效 {}: {}", attachment.content_type, e))?;
+                parts = parts.singlepart(
+                    Attachment::new(attachment.file_name.clone()).body(attachment.data.clone(), content_type),
+                );
+            }
+            builder.multipart(parts)
+        }
+        .map_err(|e| format!("构建邮件失败: {}", e))?;
+
+        self.transport()?
+            .send(&message)
+            .map(|_| ())
+            .map_err(|e| format!("发送邮件失败: {}", e))
+    }
+}
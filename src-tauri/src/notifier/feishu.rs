@@ -0,0 +1,57 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use super::{post_json, Notifier, OutgoingReport};
+
+pub struct FeishuNotifier {
+    webhook_url: String,
+    secret: Option<String>,
+}
+
+impl FeishuNotifier {
+    pub fn new(webhook_url: &str, secret: Option<String>) -> Self {
+        FeishuNotifier {
+            webhook_url: webhook_url.to_string(),
+            secret: secret.filter(|s| !s.trim().is_empty()),
+        }
+    }
+}
+
+// 飞书签名校验：以"{timestamp}\n{secret}"为密钥对空串做HmacSHA256，再Base64
+fn sign(secret: &str, timestamp: i64) -> String {
+    let key = format!("{}\n{}", timestamp, secret);
+    let mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+        .expect("HMAC可以接受任意长度的密钥");
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+impl Notifier for FeishuNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        let mut body = serde_json::json!({
+            "msg_type": "interactive",
+            "card": {
+                "header": {
+                    "title": { "tag": "plain_text", "content": report.title }
+                },
+                "elements": [
+                    { "tag": "markdown", "content": report.markdown }
+                ]
+            }
+        });
+        if let Some(secret) = &self.secret {
+            let timestamp = chrono::Utc::now().timestamp();
+            body["timestamp"] = serde_json::json!(timestamp.to_string());
+            body["sign"] = serde_json::json!(sign(secret, timestamp));
+        }
+
+        let res = post_json(&self.webhook_url, &body)?;
+        // 新版接口返回code，旧版返回StatusCode
+        let code = res["code"].as_i64().or_else(|| res["StatusCode"].as_i64());
+        match code {
+            Some(0) => Ok(()),
+            Some(code) => Err(format!("飞书返回错误 {}: {}", code, res["msg"].as_str().unwrap_or_default())),
+            None => Err(format!("无法解析飞书返回内容: {}", res)),
+        }
+    }
+}
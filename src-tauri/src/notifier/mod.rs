@@ -0,0 +1,201 @@
+// Notifier module - 统一的日报/周报外发渠道
+mod config;
+mod dingtalk;
+mod wecom;
+mod feishu;
+mod slack;
+mod webhook;
+mod email;
+
+use serde::{Deserialize, Serialize};
+use reqwest::blocking::Client;
+use crate::database::report::{generate_weekly_report, render_daily, WeeklyReportOptions};
+use crate::database::sqlite::{init_db, load_latest_daily};
+
+pub use config::{ChannelConfig, ChannelSettings, load_channels};
+
+/// 待发送的报告，内容统一使用Markdown，由各渠道转换为自己的格式
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutgoingReport {
+    pub kind: String, // daily / weekly / monthly / reminder
+    pub title: String,
+    pub markdown: String,
+    // 附件目前只有邮件渠道会发送，其他渠道只发送正文
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+/// 报告附带的文件，例如导出的Excel周报
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Attachment {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// `send_report` 要发送的内容：按用户模板渲染日报或周报，或直接发送已经写好的内容
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportRequest {
+    Daily {
+        date: String,
+        #[serde(default)]
+        user_id: Option<String>,
+    },
+    // 周报的范围与 generate_weekly_report 相同，例如 start_date 或 iso_week
+    Weekly {
+        #[serde(flatten)]
+        options: WeeklyReportOptions,
+    },
+    Custom(OutgoingReport),
+}
+
+/// 渲染为统一的Markdown报告，再由各渠道转换为自己的格式
+pub fn render_report(request: ReportRequest) -> Result<OutgoingReport, String> {
+    match request {
+        ReportRequest::Daily { date, user_id } => {
+            let conn = init_db().map_err(|e| e.to_string())?;
+            let report = load_latest_daily(&conn, &date, user_id.as_deref())?
+                .ok_or_else(|| format!("{} 没有日报", date))?;
+            Ok(OutgoingReport {
+                kind: "daily".to_string(),
+                title: format!("{} 日报", date),
+                markdown: render_daily(&report)?,
+                attachments: Vec::new(),
+            })
+        }
+        ReportRequest::Weekly { mut options } => {
+            // 只发送内容，不写文件
+            options.output_path = None;
+            let report = generate_weekly_report(options)?;
+            Ok(OutgoingReport {
+                kind: "weekly".to_string(),
+                title: format!("{} ~ {} 周报", report.start_date, report.end_date),
+                markdown: report.content,
+                attachments: Vec::new(),
+            })
+        }
+        ReportRequest::Custom(report) => Ok(report),
+    }
+}
+
+/// 单个渠道的发送结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelSendResult {
+    pub channel_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 外发渠道
+pub trait Notifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String>;
+}
+
+pub fn build_notifier(channel: &ChannelConfig) -> Box<dyn Notifier> {
+    match &channel.settings {
+        ChannelSettings::DingTalk { token, secret } => {
+            Box::new(dingtalk::DingTalkNotifier::new(token, secret.clone()))
+        }
+        ChannelSettings::WeCom { webhook_url } => Box::new(wecom::WeComNotifier::new(webhook_url)),
+        ChannelSettings::Feishu { webhook_url, secret } => {
+            Box::new(feishu::FeishuNotifier::new(webhook_url, secret.clone()))
+        }
+        ChannelSettings::Slack { webhook_url } => Box::new(slack::SlackNotifier::new(webhook_url)),
+        ChannelSettings::Webhook { url, headers } => {
+            Box::new(webhook::WebhookNotifier::new(url, headers.clone()))
+        }
+        ChannelSettings::Email(settings) => Box::new(email::EmailNotifier::new(settings.clone())),
+    }
+}
+
+// 各HTTP渠道共用的请求方法，返回解析后的JSON（非JSON响应返回Null）
+pub(crate) fn post_json(url: &str, body: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    let res = client
+        .post(url)
+        .json(body)
+        .send()
+        .map_err(|e| format!("网络请求失败: {}", e))?;
+
+    let status = res.status();
+    let text = res.text().unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("请求失败: 状态码 {}, 响应: {}", status, text));
+    }
+    Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+}
+
+/// 向多个渠道发送同一份报告，单个渠道失败不影响其他渠道，只有读取渠道配置失败时返回错误
+pub fn send_report_to_channels(channel_ids: &[String], report: &OutgoingReport) -> Result<Vec<ChannelSendResult>, String> {
+    let channels = load_channels()?;
+    Ok(channel_ids
+        .iter()
+        .map(|id| {
+            let result = match channels.iter().find(|c| &c.id == id) {
+                Some(channel) if channel.enabled => build_notifier(channel).send(report),
+                Some(_) => Err("渠道已停用".to_string()),
+                None => Err(format!("找不到渠道: {}", id)),
+            };
+            if let Err(e) = &result {
+                eprintln!("发送到渠道 {} 失败: {}", id, e);
+            }
+            ChannelSendResult {
+                channel_id: id.clone(),
+                success: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_channels(team: Option<String>) -> Result<Vec<ChannelConfig>, String> {
+    Ok(load_channels()?
+        .into_iter()
+        .filter(|c| team.is_none() || team.as_ref() == Some(&c.team))
+        .collect())
+}
+
+#[tauri::command]
+pub fn save_channel(channel: ChannelConfig) -> Result<(), String> {
+    config::save_channel(channel)
+}
+
+#[tauri::command]
+pub fn delete_channel(id: String) -> Result<(), String> {
+    config::delete_channel(&id)
+}
+
+#[tauri::command]
+pub async fn test_channel(id: String) -> Result<(), String> {
+    let report = OutgoingReport {
+        kind: "test".to_string(),
+        title: "日报助手测试消息".to_string(),
+        markdown: "这是一条测试消息，收到说明渠道配置正确。".to_string(),
+        attachments: Vec::new(),
+    };
+    let results = tauri::async_runtime::spawn_blocking(move || send_report_to_channels(&[id], &report))
+        .await
+        .map_err(|e| e.to_string())??;
+    match results.into_iter().next() {
+        Some(ChannelSendResult { error: Some(e), .. }) => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn send_report(channel_ids: Vec<String>, report: ReportRequest) -> Result<Vec<ChannelSendResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let report = render_report(report)?;
+        send_report_to_channels(&channel_ids, &report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests;
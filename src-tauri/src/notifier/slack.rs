@@ -0,0 +1,52 @@
+use super::{post_json, Notifier, OutgoingReport};
+
+pub struct SlackNotifier {
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        SlackNotifier {
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+// 把常用的Markdown语法转换为Slack的mrkdwn：标题转粗体，**粗体**转*粗体*，列表项转圆点
+fn to_mrkdwn(markdown: &str) -> String {
+    markdown
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let line = if trimmed.starts_with('#') {
+                format!("*{}*", trimmed.trim_start_matches('#').trim())
+            } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                format!("• {}", item)
+            } else {
+                line.to_string()
+            };
+            line.replace("**", "*")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Notifier for SlackNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        let body = serde_json::json!({
+            "text": report.title,
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": report.title }
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": to_mrkdwn(&report.markdown) }
+                }
+            ]
+        });
+        // Slack成功时返回纯文本"ok"，状态码即可判断结果
+        post_json(&self.webhook_url, &body).map(|_| ())
+    }
+}
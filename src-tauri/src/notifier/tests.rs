@@ -0,0 +1,317 @@
+// 各渠道对本地模拟服务的发送测试，检查签名和请求体格式
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use super::config::EmailSettings;
+use super::*;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 模拟服务收到的HTTP请求
+struct MockRequest {
+    target: String, // 路径和查询参数
+    headers: HashMap<String, String>, // 名称为小写
+    body: serde_json::Value,
+}
+
+// 启动只处理一个请求的HTTP服务，返回服务地址和收到的请求
+fn mock_http(status: u16, response: &str) -> (String, Receiver<MockRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let response = response.to_string();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let target = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+        let length: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            response.len(),
+            response
+        )
+        .unwrap();
+        tx.send(MockRequest {
+            target,
+            headers,
+            body: serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+        })
+        .unwrap();
+    });
+    (url, rx)
+}
+
+/// 模拟SMTP服务收到的邮件
+struct MockMail {
+    commands: Vec<String>,
+    data: String,
+}
+
+// 启动只接收一封邮件的SMTP服务（不加密、不认证），返回端口和收到的邮件
+fn mock_smtp() -> (u16, Receiver<MockMail>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(b"220 mock ESMTP\r\n").unwrap();
+
+        let mut commands = Vec::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let command = line.trim_end().to_string();
+            if command.eq_ignore_ascii_case("DATA") {
+                stream.write_all(b"354 end with <CRLF>.<CRLF>\r\n").unwrap();
+                let mut data = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == ".\r\n" {
+                        break;
+                    }
+                    data.push_str(&line);
+                }
+                stream.write_all(b"250 queued\r\n").unwrap();
+                tx.send(MockMail { commands, data }).unwrap();
+                return;
+            }
+            let reply: &[u8] = if command.starts_with("QUIT") { b"221 bye\r\n" } else { b"250 ok\r\n" };
+            commands.push(command);
+            stream.write_all(reply).unwrap();
+        }
+    });
+    (port, rx)
+}
+
+fn channel(settings: ChannelSettings) -> ChannelConfig {
+    ChannelConfig {
+        id: "test".to_string(),
+        name: "测试".to_string(),
+        team: "default".to_string(),
+        enabled: true,
+        settings,
+    }
+}
+
+fn report() -> OutgoingReport {
+    OutgoingReport {
+        kind: "daily".to_string(),
+        title: "2025-06-09 日报".to_string(),
+        markdown: "## 已完成\n- 修复登录问题\n- **发布** 新版本".to_string(),
+        attachments: Vec::new(),
+    }
+}
+
+fn hmac_base64(key: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+    mac.update(message.as_bytes());
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+fn query_param(target: &str, name: &str) -> Option<String> {
+    target
+        .split_once('?')?
+        .1
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(&format!("{}=", name)))
+        .map(|value| urlencoding::decode(value).unwrap().into_owned())
+}
+
+#[test]
+fn dingtalk_sends_signed_markdown() {
+    let (url, rx) = mock_http(200, r#"{"errcode":0,"errmsg":"ok"}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::DingTalk {
+        token: format!("{}/robot/send?access_token=abc", url),
+        secret: Some("SECtest".to_string()),
+    }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert!(request.target.starts_with("/robot/send?access_token=abc&timestamp="));
+    let timestamp = query_param(&request.target, "timestamp").unwrap();
+    let sign = query_param(&request.target, "sign").unwrap();
+    assert_eq!(sign, hmac_base64("SECtest", &format!("{}\nSECtest", timestamp)));
+
+    assert_eq!(request.body["msgtype"], "markdown");
+    assert_eq!(request.body["markdown"]["title"], "2025-06-09 日报");
+    let text = request.body["markdown"]["text"].as_str().unwrap();
+    assert!(text.starts_with("### 2025-06-09 日报\n\n"));
+    assert!(text.contains("- 修复登录问题"));
+}
+
+#[test]
+fn dingtalk_reports_api_error() {
+    let (url, _rx) = mock_http(200, r#"{"errcode":310000,"errmsg":"sign not match"}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::DingTalk {
+        token: format!("{}/robot/send?access_token=abc", url),
+        secret: None,
+    }));
+    let error = notifier.send(&report()).unwrap_err();
+    assert!(error.contains("sign not match"), "{}", error);
+}
+
+#[test]
+fn wecom_sends_markdown() {
+    let (url, rx) = mock_http(200, r#"{"errcode":0,"errmsg":"ok"}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::WeCom {
+        webhook_url: format!("{}/cgi-bin/webhook/send?key=k1", url),
+    }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(request.target, "/cgi-bin/webhook/send?key=k1");
+    assert_eq!(request.body["msgtype"], "markdown");
+    let content = request.body["markdown"]["content"].as_str().unwrap();
+    assert!(content.starts_with("## 2025-06-09 日报\n## 已完成"));
+}
+
+#[test]
+fn wecom_truncates_long_content_and_reports_errors() {
+    let (url, rx) = mock_http(200, r#"{"errcode":93000,"errmsg":"invalid webhook url"}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::WeCom { webhook_url: url }));
+    let mut long = report();
+    long.markdown = "工作内容".repeat(2000);
+    let error = notifier.send(&long).unwrap_err();
+    assert!(error.contains("93000"), "{}", error);
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    let content = request.body["markdown"]["content"].as_str().unwrap();
+    assert!(content.len() <= 4096);
+}
+
+#[test]
+fn feishu_sends_signed_card() {
+    let (url, rx) = mock_http(200, r#"{"code":0,"msg":"success"}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::Feishu {
+        webhook_url: format!("{}/open-apis/bot/v2/hook/h1", url),
+        secret: Some("feishu-secret".to_string()),
+    }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(request.target, "/open-apis/bot/v2/hook/h1");
+    let body = &request.body;
+    assert_eq!(body["msg_type"], "interactive");
+    assert_eq!(body["card"]["header"]["title"]["content"], "2025-06-09 日报");
+    assert_eq!(body["card"]["elements"][0]["tag"], "markdown");
+    assert_eq!(body["card"]["elements"][0]["content"], report().markdown);
+
+    let timestamp = body["timestamp"].as_str().unwrap();
+    assert_eq!(body["sign"], hmac_base64(&format!("{}\nfeishu-secret", timestamp), ""));
+}
+
+#[test]
+fn feishu_without_secret_is_unsigned() {
+    let (url, rx) = mock_http(200, r#"{"StatusCode":0}"#);
+    let notifier = build_notifier(&channel(ChannelSettings::Feishu { webhook_url: url, secret: None }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert!(request.body.get("sign").is_none());
+    assert!(request.body.get("timestamp").is_none());
+}
+
+#[test]
+fn slack_converts_markdown_to_mrkdwn() {
+    let (url, rx) = mock_http(200, "ok");
+    let notifier = build_notifier(&channel(ChannelSettings::Slack {
+        webhook_url: format!("{}/services/T1/B1/x", url),
+    }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(request.target, "/services/T1/B1/x");
+    assert_eq!(request.body["text"], "2025-06-09 日报");
+    assert_eq!(request.body["blocks"][0]["type"], "header");
+    assert_eq!(request.body["blocks"][1]["text"]["type"], "mrkdwn");
+    assert_eq!(request.body["blocks"][1]["text"]["text"], "*已完成*\n• 修复登录问题\n• *发布* 新版本");
+}
+
+#[test]
+fn slack_reports_http_error() {
+    let (url, _rx) = mock_http(404, "no_service");
+    let notifier = build_notifier(&channel(ChannelSettings::Slack { webhook_url: url }));
+    let error = notifier.send(&report()).unwrap_err();
+    assert!(error.contains("404"), "{}", error);
+}
+
+#[test]
+fn webhook_sends_headers_and_json_body() {
+    let (url, rx) = mock_http(204, "");
+    let notifier = build_notifier(&channel(ChannelSettings::Webhook {
+        url: format!("{}/hooks/daily", url),
+        headers: HashMap::from([("X-Token".to_string(), "t0ken".to_string())]),
+    }));
+    notifier.send(&report()).unwrap();
+
+    let request = rx.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(request.target, "/hooks/daily");
+    assert_eq!(request.headers.get("x-token").map(String::as_str), Some("t0ken"));
+    assert_eq!(request.body["kind"], "daily");
+    assert_eq!(request.body["title"], "2025-06-09 日报");
+    assert_eq!(request.body["content"], report().markdown);
+    assert_eq!(request.body["format"], "markdown");
+    assert!(request.body["sent_at"].is_string());
+}
+
+#[test]
+fn email_sends_body_and_attachment() {
+    let (port, rx) = mock_smtp();
+    let notifier = build_notifier(&channel(ChannelSettings::Email(EmailSettings {
+        host: "127.0.0.1".to_string(),
+        port: Some(port),
+        username: None,
+        password: None,
+        from: "bot@example.com".to_string(),
+        to: vec!["lead@example.com".to_string()],
+        security: "none".to_string(),
+    })));
+    let mut report = report();
+    report.title = "Weekly report".to_string();
+    report.attachments.push(Attachment {
+        file_name: "weekly.xlsx".to_string(),
+        content_type: "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+        data: vec![0x50, 0x4B, 0x03, 0x04],
+    });
+    notifier.send(&report).unwrap();
+
+    let mail = rx.recv_timeout(TIMEOUT).unwrap();
+    assert!(mail.commands.iter().any(|c| c == "MAIL FROM:<bot@example.com>"));
+    assert!(mail.commands.iter().any(|c| c == "RCPT TO:<lead@example.com>"));
+    assert!(mail.data.contains("Subject: Weekly report"));
+    assert!(mail.data.contains("multipart/mixed"));
+    assert!(mail.data.contains("filename=\"weekly.xlsx\""));
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use reqwest::blocking::Client;
+use super::{Notifier, OutgoingReport};
+
+/// 通用JSON Webhook，请求体为 {kind, title, content, format, sent_at}
+pub struct WebhookNotifier {
+    url: String,
+    headers: HashMap<String, String>,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, headers: HashMap<String, String>) -> Self {
+        WebhookNotifier {
+            url: url.to_string(),
+            headers,
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+        let mut request = client.post(&self.url).json(&serde_json::json!({
+            "kind": report.kind,
+            "title": report.title,
+            "content": report.markdown,
+            "format": "markdown",
+            "sent_at": chrono::Local::now().to_rfc3339()
+        }));
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let res = request.send().map_err(|e| format!("网络请求失败: {}", e))?;
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            let status = res.status();
+            Err(format!("Webhook返回状态码 {}: {}", status, res.text().unwrap_or_default()))
+        }
+    }
+}
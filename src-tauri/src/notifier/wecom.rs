@@ -0,0 +1,44 @@
+use super::{post_json, Notifier, OutgoingReport};
+
+// 企业微信机器人markdown消息的内容上限（字节）
+const WECOM_MARKDOWN_LIMIT: usize = 4096;
+
+pub struct WeComNotifier {
+    webhook_url: String,
+}
+
+impl WeComNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        WeComNotifier {
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+// 按字符边界截断，避免切断多字节的中文字符
+fn truncate_bytes(text: &str, limit: usize) -> &str {
+    if text.len() <= limit {
+        return text;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+impl Notifier for WeComNotifier {
+    fn send(&self, report: &OutgoingReport) -> Result<(), String> {
+        let content = format!("## {}\n{}", report.title, report.markdown);
+        let body = serde_json::json!({
+            "msgtype": "markdown",
+            "markdown": { "content": truncate_bytes(&content, WECOM_MARKDOWN_LIMIT) }
+        });
+        let res = post_json(&self.webhook_url, &body)?;
+        match res["errcode"].as_i64() {
+            Some(0) => Ok(()),
+            Some(code) => Err(format!("企业微信返回错误 {}: {}", code, res["errmsg"].as_str().unwrap_or_default())),
+            None => Err(format!("无法解析企业微信返回内容: {}", res)),
+        }
+    }
+}
@@ -1 +1,355 @@
-// 文件内容已被移除，通知功能请用前端 JS/TS 调用 @tauri-apps/plugin-notification 实现。 
\ No newline at end of file
+use std::fs;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use crate::database::sqlite::init_db;
+use crate::database::scheduler::{register_job, ScheduledJob};
+use crate::database::calendar::WorkCalendar;
+use crate::database::coverage::get_report_coverage;
+
+const RULES_FILE: &str = "reminder_rules.json";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 提醒触发条件
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReminderTrigger {
+    // 每天固定时间提醒写日报，当天已写则不提醒
+    DailyAt { time: String, workdays_only: bool },
+    // 上一个工作日的日报缺失时提醒
+    MissingYesterday { time: String },
+    // 最近 days 天内有缺报的工作日时提醒，提醒内容附带缺报日期
+    MissingReports { time: String, days: u32 },
+    // 每周固定某天提醒，weekday 取值 1-7（周一为1）
+    Weekly { weekday: u32, time: String },
+}
+
+/// 提醒规则
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReminderRule {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub enabled: bool,
+    pub user_id: Option<String>,
+    pub trigger: ReminderTrigger,
+}
+
+/// 提醒的持久化状态
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReminderState {
+    pub rule_id: String,
+    pub last_fired_at: Option<String>,
+    pub snoozed_until: Option<String>,
+    pub dismissed_on: Option<String>,
+}
+
+/// 推送给前端的提醒事件，前端据此展示"稍后提醒/不再提醒"按钮
+#[derive(Serialize, Clone, Debug)]
+pub struct ReminderEvent {
+    pub rule_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+fn default_rules() -> Vec<ReminderRule> {
+    vec![
+        ReminderRule {
+            id: "daily".to_string(),
+            title: "写日报".to_string(),
+            body: "快下班了，记得写今天的日报。".to_string(),
+            enabled: true,
+            user_id: None,
+            trigger: ReminderTrigger::DailyAt { time: "17:30".to_string(), workdays_only: true },
+        },
+        ReminderRule {
+            id: "missing_yesterday".to_string(),
+            title: "补写日报".to_string(),
+            body: "上一个工作日的日报还没有填写。".to_string(),
+            enabled: true,
+            user_id: None,
+            trigger: ReminderTrigger::MissingYesterday { time: "09:30".to_string() },
+        },
+        ReminderRule {
+            id: "weekly_review".to_string(),
+            title: "检查周报".to_string(),
+            body: "今天是周五，记得检查本周周报。".to_string(),
+            enabled: true,
+            user_id: None,
+            trigger: ReminderTrigger::Weekly { weekday: 5, time: "16:00".to_string() },
+        },
+    ]
+}
+
+pub fn load_rules() -> Vec<ReminderRule> {
+    match fs::read_to_string(RULES_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            eprintln!("解析提醒规则失败，使用默认规则: {}", e);
+            default_rules()
+        }),
+        Err(_) => default_rules(),
+    }
+}
+
+fn write_rules(rules: &[ReminderRule]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(rules)
+        .map_err(|e| format!("序列化提醒规则失败: {}", e))?;
+    fs::write(RULES_FILE, json).map_err(|e| format!("保存提醒规则失败: {}", e))
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("时间格式不正确: {}, 应为HH:MM格式", time))
+}
+
+fn validate_rule(rule: &ReminderRule) -> Result<(), String> {
+    if rule.id.trim().is_empty() {
+        return Err("提醒规则ID不能为空".to_string());
+    }
+    match &rule.trigger {
+        ReminderTrigger::DailyAt { time, .. }
+        | ReminderTrigger::MissingYesterday { time }
+        | ReminderTrigger::MissingReports { time, .. } => parse_time(time).map(|_| ()),
+        ReminderTrigger::Weekly { weekday, time } => {
+            if !(1..=7).contains(weekday) {
+                return Err(format!("星期取值应为1-7: {}", weekday));
+            }
+            parse_time(time).map(|_| ())
+        }
+    }
+}
+
+fn open_db() -> Result<Connection, String> {
+    let conn = init_db().map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reminder_state (
+            rule_id TEXT PRIMARY KEY,
+            last_fired_at TEXT,
+            snoozed_until TEXT,
+            dismissed_on TEXT
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn load_state(conn: &Connection, rule_id: &str) -> Result<ReminderState, String> {
+    let state = conn.query_row(
+        "SELECT last_fired_at, snoozed_until, dismissed_on FROM reminder_state WHERE rule_id = ?1",
+        [rule_id],
+        |row| Ok(ReminderState {
+            rule_id: rule_id.to_string(),
+            last_fired_at: row.get(0)?,
+            snoozed_until: row.get(1)?,
+            dismissed_on: row.get(2)?,
+        }),
+    ).optional().map_err(|e| e.to_string())?;
+
+    Ok(state.unwrap_or(ReminderState {
+        rule_id: rule_id.to_string(),
+        last_fired_at: None,
+        snoozed_until: None,
+        dismissed_on: None,
+    }))
+}
+
+fn save_state(conn: &Connection, state: &ReminderState) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO reminder_state (rule_id, last_fired_at, snoozed_until, dismissed_on) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(rule_id) DO UPDATE SET
+            last_fired_at = excluded.last_fired_at, snoozed_until = excluded.snoozed_until, dismissed_on = excluded.dismissed_on",
+        params![state.rule_id, state.last_fired_at, state.snoozed_until, state.dismissed_on],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 日期范围内（含首尾）缺报的工作日，来自日报覆盖统计
+fn missing_dates(user_id: Option<&str>, start: NaiveDate, end: NaiveDate) -> Vec<String> {
+    get_report_coverage(
+        user_id.map(|u| u.to_string()),
+        start.format("%Y-%m-%d").to_string(),
+        end.format("%Y-%m-%d").to_string(),
+    )
+    .map(|coverage| coverage.missing_dates)
+    .unwrap_or_else(|e| {
+        eprintln!("统计缺报日期失败: {}", e);
+        Vec::new()
+    })
+}
+
+fn is_filled(user_id: Option<&str>, date: NaiveDate) -> bool {
+    let date = date.format("%Y-%m-%d").to_string();
+    get_report_coverage(user_id.map(|u| u.to_string()), date.clone(), date)
+        .map(|coverage| coverage.days.iter().any(|d| d.status == "filled"))
+        .unwrap_or(false)
+}
+
+// 规则需要提醒的缺报日期，没有缺报时为空
+fn rule_missing_dates(rule: &ReminderRule, calendar: &WorkCalendar, today: NaiveDate) -> Vec<String> {
+    let user_id = rule.user_id.as_deref();
+    match &rule.trigger {
+        ReminderTrigger::MissingYesterday { .. } => {
+            let previous = calendar.previous_workday(today);
+            missing_dates(user_id, previous, previous)
+        }
+        ReminderTrigger::MissingReports { days, .. } => {
+            missing_dates(user_id, today - chrono::Duration::days(*days as i64), today)
+        }
+        _ => Vec::new(),
+    }
+}
+
+// 规则在今天的触发时间；今天不适用该规则或条件不满足时返回None
+fn due_time(rule: &ReminderRule, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let today = now.date();
+    let user_id = rule.user_id.as_deref();
+    // 工作日按法定节假日、调休和个人请假判断
+    let calendar = WorkCalendar::for_user(user_id);
+    match &rule.trigger {
+        ReminderTrigger::DailyAt { time, workdays_only } => {
+            if *workdays_only && !calendar.is_workday(today) {
+                return None;
+            }
+            if is_filled(user_id, today) {
+                return None;
+            }
+            parse_time(time).ok().map(|t| today.and_time(t))
+        }
+        ReminderTrigger::MissingYesterday { time } | ReminderTrigger::MissingReports { time, .. } => {
+            if !calendar.is_workday(today) || rule_missing_dates(rule, &calendar, today).is_empty() {
+                return None;
+            }
+            parse_time(time).ok().map(|t| today.and_time(t))
+        }
+        ReminderTrigger::Weekly { weekday, time } => {
+            if today.weekday().number_from_monday() != *weekday {
+                return None;
+            }
+            parse_time(time).ok().map(|t| today.and_time(t))
+        }
+    }
+}
+
+fn parse_datetime(value: &Option<String>) -> Option<NaiveDateTime> {
+    value.as_ref().and_then(|v| NaiveDateTime::parse_from_str(v, DATETIME_FORMAT).ok())
+}
+
+/// 检查所有规则，返回本次需要弹出的提醒，并更新持久化状态
+pub fn collect_due_reminders(now: NaiveDateTime) -> Result<Vec<ReminderEvent>, String> {
+    let conn = open_db()?;
+    let today = now.date().format("%Y-%m-%d").to_string();
+    let mut events = Vec::new();
+
+    for rule in load_rules().into_iter().filter(|r| r.enabled) {
+        let mut state = load_state(&conn, &rule.id)?;
+        if state.dismissed_on.as_deref() == Some(today.as_str()) {
+            continue;
+        }
+
+        // 之前某天留下的稍后提醒已经失效，按当天的规则重新判断
+        if parse_datetime(&state.snoozed_until).is_some_and(|until| until.date() < now.date()) {
+            state.snoozed_until = None;
+            save_state(&conn, &state)?;
+        }
+
+        let is_due = || due_time(&rule, now).is_some_and(|due| now >= due);
+        let fire = match parse_datetime(&state.snoozed_until) {
+            // 稍后提醒：到点后再次提醒，不受当天是否已提醒的限制，但不会早于规则的触发时间
+            Some(snoozed_until) => now >= snoozed_until && is_due(),
+            None => {
+                let fired_today = parse_datetime(&state.last_fired_at)
+                    .is_some_and(|last| last.date() == now.date());
+                !fired_today && is_due()
+            }
+        };
+
+        if fire {
+            state.last_fired_at = Some(now.format(DATETIME_FORMAT).to_string());
+            state.snoozed_until = None;
+            save_state(&conn, &state)?;
+
+            let calendar = WorkCalendar::for_user(rule.user_id.as_deref());
+            let missing = rule_missing_dates(&rule, &calendar, now.date());
+            let body = if missing.is_empty() {
+                rule.body.clone()
+            } else {
+                format!("{}\n缺少日报：{}", rule.body, missing.join("、"))
+            };
+            events.push(ReminderEvent {
+                rule_id: rule.id.clone(),
+                title: rule.title.clone(),
+                body,
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+fn show_reminder(app: &AppHandle, event: &ReminderEvent) {
+    if let Err(e) = app.notification().builder().title(&event.title).body(&event.body).show() {
+        eprintln!("显示系统通知失败: {}", e);
+    }
+    let _ = app.emit("reminder", event.clone());
+}
+
+/// 在调度器上注册提醒任务，每分钟检查一次规则
+pub fn register_reminder_job(app: AppHandle) {
+    register_job(ScheduledJob::new("reminders", "桌面提醒", "* * * * *", "reminder"), move |_, _| {
+        for event in collect_due_reminders(Local::now().naive_local())? {
+            show_reminder(&app, &event);
+        }
+        Ok(())
+    });
+}
+
+#[tauri::command]
+pub fn list_reminder_rules() -> Vec<ReminderRule> {
+    load_rules()
+}
+
+#[tauri::command]
+pub fn save_reminder_rule(rule: ReminderRule) -> Result<(), String> {
+    validate_rule(&rule)?;
+    let mut rules = load_rules();
+    match rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule,
+        None => rules.push(rule),
+    }
+    write_rules(&rules)
+}
+
+#[tauri::command]
+pub fn delete_reminder_rule(id: String) -> Result<(), String> {
+    let mut rules = load_rules();
+    rules.retain(|r| r.id != id);
+    write_rules(&rules)
+}
+
+#[tauri::command]
+pub fn snooze_reminder(rule_id: String, minutes: u32) -> Result<(), String> {
+    let conn = open_db()?;
+    let mut state = load_state(&conn, &rule_id)?;
+    let until = Local::now().naive_local() + chrono::Duration::minutes(minutes.max(1) as i64);
+    state.snoozed_until = Some(until.format(DATETIME_FORMAT).to_string());
+    save_state(&conn, &state)
+}
+
+#[tauri::command]
+pub fn dismiss_reminder(rule_id: String) -> Result<(), String> {
+    let conn = open_db()?;
+    let mut state = load_state(&conn, &rule_id)?;
+    state.snoozed_until = None;
+    state.dismissed_on = Some(Local::now().format("%Y-%m-%d").to_string());
+    save_state(&conn, &state)
+}
+
+#[tauri::command]
+pub fn get_reminder_states() -> Result<Vec<ReminderState>, String> {
+    let conn = open_db()?;
+    load_rules()
+        .iter()
+        .map(|rule| load_state(&conn, &rule.id))
+        .collect()
+}
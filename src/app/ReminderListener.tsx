@@ -0,0 +1,78 @@
+"use client"
+import { useEffect, useState } from "react"
+import { listen, UnlistenFn } from "@tauri-apps/api/event"
+import { invoke } from "@tauri-apps/api/core"
+import { Button } from "@/components/ui"
+
+// 与后端 notify::ReminderEvent 对应
+interface ReminderEvent {
+    rule_id: string
+    title: string
+    body: string
+}
+
+const SNOOZE_MINUTES = 10
+
+export default function ReminderListener() {
+    const [reminders, setReminders] = useState<ReminderEvent[]>([])
+    const [error, setError] = useState("")
+
+    useEffect(() => {
+        // 监听后端定时任务推送的日报提醒，同一规则只保留最新的一条
+        let unlisten: UnlistenFn
+        listen<ReminderEvent>("reminder", event => {
+            setReminders(prev => [...prev.filter(r => r.rule_id !== event.payload.rule_id), event.payload])
+        }).then(fn => { unlisten = fn })
+
+        return () => { if (unlisten) unlisten() }
+    }, [])
+
+    const close = (ruleId: string) => {
+        setReminders(prev => prev.filter(r => r.rule_id !== ruleId))
+    }
+
+    const snooze = async (ruleId: string) => {
+        try {
+            await invoke("snooze_reminder", { ruleId, minutes: SNOOZE_MINUTES })
+            close(ruleId)
+        } catch (e) {
+            setError(`设置稍后提醒失败: ${e}`)
+        }
+    }
+
+    const dismiss = async (ruleId: string) => {
+        try {
+            await invoke("dismiss_reminder", { ruleId })
+            close(ruleId)
+        } catch (e) {
+            setError(`关闭提醒失败: ${e}`)
+        }
+    }
+
+    if (reminders.length === 0) {
+        return null
+    }
+
+    return (
+        <div className="fixed bottom-4 right-4 z-50 flex flex-col gap-3 w-80">
+            {error && <div className="text-sm text-red-600 bg-red-50 border border-red-200 rounded-md p-2">{error}</div>}
+            {reminders.map(reminder => (
+                <div key={reminder.rule_id} className="bg-white border border-gray-200 rounded-lg shadow-lg p-4">
+                    <div className="font-medium text-gray-900 mb-1">{reminder.title}</div>
+                    <div className="text-sm text-gray-600 whitespace-pre-line mb-3">{reminder.body}</div>
+                    <div className="flex gap-2 justify-end">
+                        <Button size="small" variant="outline" onClick={() => snooze(reminder.rule_id)}>
+                            {SNOOZE_MINUTES}分钟后提醒
+                        </Button>
+                        <Button size="small" variant="secondary" onClick={() => dismiss(reminder.rule_id)}>
+                            今天不再提醒
+                        </Button>
+                        <Button size="small" variant="ghost" onClick={() => close(reminder.rule_id)}>
+                            关闭
+                        </Button>
+                    </div>
+                </div>
+            ))}
+        </div>
+    )
+}
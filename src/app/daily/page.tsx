@@ -388,6 +388,31 @@ export default function DailyPage() {
         }
     }
 
+    // 把后台记录的当天工作时间线（提交、分支切换）添加到今日已完成事项
+    const importTimeline = async () => {
+        try {
+            setIsLoading(true)
+            setStatus("正在读取工作时间线...")
+
+            // 先补录一次，包含刚刚发生的操作
+            await invoke("scan_work_log_now")
+            const timeline = await invoke<string>("get_daily_timeline", { date })
+
+            if (timeline) {
+                const lines = timeline.split("\n").map(line => `- ${line}`).join("\n")
+                setCompleted(prev => prev ? `${prev}\n\n${lines}` : lines)
+                setStatus("工作时间线已添加到今日已完成事项")
+            } else {
+                setStatus("当天没有工作记录，请先在设置中添加需要记录的仓库")
+            }
+        } catch (error) {
+            console.error("读取工作时间线失败:", error)
+            setStatus(`读取工作时间线失败: ${error}`)
+        } finally {
+            setIsLoading(false)
+        }
+    }
+
     // 日期变更时加载对应日报
     const handleDateChange = (e: React.ChangeEvent<HTMLInputElement>) => {
         const newDate = e.target.value
@@ -446,6 +471,14 @@ export default function DailyPage() {
                         >
                             导入Git更改
                         </Button>
+                        <Button
+                            variant="outline"
+                            onClick={importTimeline}
+                            loading={isLoading || pageLoading}
+                            disabled={!date || pageLoading}
+                        >
+                            导入工作时间线
+                        </Button>
                     </div>
                 }
             >
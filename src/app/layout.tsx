@@ -4,6 +4,7 @@ import { Inter } from 'next/font/google'
 import { Geist, Geist_Mono } from "next/font/google"
 import "./nav-styles.css"
 import IdleListener from "./IdleListener"
+import ReminderListener from "./ReminderListener"
 import ClientLayout from "@/components/ClientLayout"
 
 const inter = Inter({ subsets: ['latin'] })
@@ -32,6 +33,7 @@ export default function RootLayout({
     <html lang="zh-CN">
       <body className={`${geistSans.variable} ${geistMono.variable} antialiased`}>
         <IdleListener />
+        <ReminderListener />
         <ClientLayout>{children}</ClientLayout>
       </body>
     </html>
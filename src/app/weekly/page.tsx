@@ -176,7 +176,8 @@ export default function WeeklyPage() {
             const result = await invoke<{
                 tasks: Task[],
                 nextWeekPlan: string,
-                column_indices: Record<string, number>
+                column_indices: Record<string, number>,
+                warnings?: { row: number, field: string, value: string, message: string }[]
             }>("parse_excel_template", {
                 excelData: Array.from(uint8Array)
             })
@@ -200,6 +201,14 @@ export default function WeeklyPage() {
                 setStatus(prev => prev + "，并找到了下周计划")
             }
 
+            // 显示无法识别的单元格，便于用户核对
+            if (result.warnings && result.warnings.length > 0) {
+                const details = result.warnings
+                    .map(w => `第${w.row}行 ${w.value}: ${w.message}`)
+                    .join("；")
+                setStatus(prev => `${prev}。有${result.warnings!.length}个单元格未能识别（${details}）`)
+            }
+
             setHasTemplate(true)
         } catch (e) {
             console.error("Excel模板解析错误:", e)
@@ -215,13 +224,25 @@ export default function WeeklyPage() {
         }
 
         try {
+            // 先选择保存位置
+            const path = await invoke<string | null>('select_save_path', {
+                title: '导出周报',
+                defaultPath: `周报_${startDate}_至_${endDate}.xlsx`,
+                filters: [{
+                    name: 'Excel',
+                    extensions: ['xlsx']
+                }]
+            })
+            if (!path) return
+
             setStatus("正在生成Excel...")
             // 准备周报数据
             const reportData = {
                 startDate,
                 endDate,
                 tasks,
-                nextWeekPlan
+                nextWeekPlan,
+                sink: { type: "path", path }
             }
 
             // 调用Rust后端生成Excel
@@ -458,7 +479,7 @@ export default function WeeklyPage() {
                     <div className="task-table-actions">
                         <button className="secondary-btn" onClick={exportToExcel}>导出Excel</button>
                         <button className="secondary-btn" onClick={() => fileInputRef.current?.click()}>导入模板</button>
-                        <input type="file" ref={fileInputRef} style={{ display: 'none' }} accept=".xlsx,.xls" onChange={e => handleTemplateFile(e.target.files)} />
+                        <input type="file" ref={fileInputRef} style={{ display: 'none' }} accept=".xlsx,.xls,.xlsb,.ods,.csv,.tsv,.txt" onChange={e => handleTemplateFile(e.target.files)} />
                         <button className="secondary-btn" onClick={saveAsTemplate}>保存模板</button>
                         <button className="secondary-btn" onClick={loadSavedTemplate}>加载模板</button>
                         <button className="secondary-btn" onClick={generateFromDaily}>从日报生成</button>